@@ -0,0 +1,76 @@
+//! Records the version of rsass the crate is built against, as resolved in the
+//! lockfile of the build, so the backend id follows patch releases too
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Returns the version requirement on rsass in this crate's manifest, e.g. `0.25.2`
+fn rsass_requirement(manifest_dir: &Path) -> Option<String> {
+    let manifest = fs::read_to_string(manifest_dir.join("Cargo.toml")).ok()?;
+
+    manifest.lines().find_map(|line| {
+        let requirement = line.trim().strip_prefix("rsass")?.trim_start().strip_prefix('=')?;
+        Some(requirement.trim().trim_matches('"').to_string())
+    })
+}
+
+/// Returns the prefix shared by every version semver-compatible with `requirement`,
+/// e.g. `0.25.` for `0.25.2`
+fn compatible_prefix(requirement: &str) -> String {
+    let mut parts = requirement.trim_start_matches('^').split('.');
+    match (parts.next(), parts.next()) {
+        (Some("0"), Some(minor)) => format!("0.{}.", minor),
+        (Some(major), _) => format!("{}.", major),
+        _ => String::new(),
+    }
+}
+
+/// Returns the `Cargo.lock` closest to one of `dirs`, looking in them and their ancestors
+fn find_lockfile(dirs: &[PathBuf]) -> Option<PathBuf> {
+    dirs.iter()
+        .flat_map(|dir| dir.ancestors())
+        .map(|dir| dir.join("Cargo.lock"))
+        .find(|lockfile| lockfile.is_file())
+}
+
+/// Returns the version of rsass starting with `prefix` in `lockfile`
+fn locked_version(lockfile: &Path, prefix: &str) -> Option<String> {
+    let lock = fs::read_to_string(lockfile).ok()?;
+    let mut lines = lock.lines();
+
+    while let Some(line) = lines.next() {
+        if line.trim() != "name = \"rsass\"" {
+            continue;
+        }
+
+        let version = lines.next()?.trim().strip_prefix("version = ")?.trim_matches('"');
+        if version.starts_with(prefix) {
+            return Some(version.to_string());
+        }
+    }
+
+    None
+}
+
+fn main() {
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    println!("cargo:rerun-if-changed=Cargo.toml");
+
+    let requirement = rsass_requirement(&manifest_dir).unwrap_or_default();
+    let prefix = compatible_prefix(&requirement);
+
+    // The lockfile of a dependent project sits above its target directory
+    let lockfile = find_lockfile(&[out_dir, manifest_dir]);
+    if let Some(lockfile) = &lockfile {
+        println!("cargo:rerun-if-changed={}", lockfile.display());
+    }
+
+    // Without a lockfile, the requirement is the best known approximation
+    let version = lockfile
+        .and_then(|lockfile| locked_version(&lockfile, &prefix))
+        .unwrap_or(requirement);
+
+    println!("cargo:rustc-env=SASS_ROCKET_FAIRING_RSASS_VERSION={}", version);
+}
@@ -0,0 +1,52 @@
+//! Compiling sass sources shipped as an archive.
+//! Only available with the `archive` feature
+
+use std::collections::HashMap;
+use std::io::{Read, Seek};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::SassError;
+
+/// Distinguishes the extraction directories of concurrent calls
+static EXTRACTIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// Extracts a zip archive of sass sources to a temporary directory and compiles every
+/// source in it (partials excluded), e.g. for an uploaded theme. Returns the css keyed
+/// by output path with forward slashes, laid out like the archive: `theme/main.scss`
+/// becomes `theme/main.css`. Fails with the first compile error, or if an entry
+/// would be extracted outside the temporary directory
+pub fn compile_archive<R>(reader: R, format: rsass::output::Format) -> Result<HashMap<String, String>, SassError>
+where
+    R: Read + Seek,
+{
+    let dir = std::env::temp_dir().join(format!(
+        "sass-rocket-fairing-archive-{}-{}",
+        std::process::id(),
+        EXTRACTIONS.fetch_add(1, Ordering::SeqCst)
+    ));
+    let to_error = |e: zip::result::ZipError| SassError::new("<archive>", e.to_string());
+
+    // `extract` rejects the whole archive if an entry escapes the directory (e.g. `../x.scss`)
+    let result = zip::ZipArchive::new(reader)
+        .and_then(|mut archive| archive.extract(&dir))
+        .map_err(to_error)
+        .and_then(|_| crate::build::compile_sources(&dir, format));
+
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let compiled = result?
+        .into_iter()
+        .map(|(source, css)| {
+            let output = source
+                .with_extension("css")
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+
+            (output, css)
+        })
+        .collect();
+
+    Ok(compiled)
+}
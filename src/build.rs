@@ -0,0 +1,62 @@
+//! Compiling at build time, from a `build.rs`.
+//! Nothing here needs a running Rocket instance
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use walkdir::WalkDir;
+
+use crate::SassError;
+
+/// Compiles every source in `sass_dir` (partials excluded) and writes the css into
+/// `out_dir`, named by [`crate::default_output_name`]. Returns the written files.
+///
+/// Also tells cargo to rerun the build script when `sass_dir` changes, so it is
+/// meant to be called from a `build.rs`:
+///
+/// ```rust,no_run
+/// // build.rs
+/// let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap());
+/// sass_rocket_fairing::build::compile_dir("static/sass", out_dir, Default::default()).unwrap();
+/// ```
+///
+/// The css can then be embedded with
+/// `include_str!(concat!(env!("OUT_DIR"), "/main.css"))`
+pub fn compile_dir(
+    sass_dir: impl AsRef<Path>,
+    out_dir: impl AsRef<Path>,
+    format: rsass::output::Format,
+) -> Result<Vec<PathBuf>, SassError> {
+    let (sass_dir, out_dir) = (sass_dir.as_ref(), out_dir.as_ref());
+    println!("cargo:rerun-if-changed={}", sass_dir.display());
+
+    fs::create_dir_all(out_dir).map_err(|e| SassError::new(out_dir, e.to_string()))?;
+
+    let mut written = Vec::new();
+    for (source, css) in compile_sources(sass_dir, format)? {
+        let output_path = out_dir.join(crate::default_output_name(&source));
+
+        fs::write(&output_path, css).map_err(|e| SassError::new(&output_path, e.to_string()))?;
+        written.push(output_path);
+    }
+
+    written.sort();
+    Ok(written)
+}
+
+/// Compiles every source in `sass_dir` (partials excluded), returning each source's
+/// path relative to `sass_dir` along with its css. Fails with the first compile error
+pub(crate) fn compile_sources(sass_dir: &Path, format: rsass::output::Format) -> Result<Vec<(PathBuf, String)>, SassError> {
+    let mut compiled = Vec::new();
+
+    for entry in WalkDir::new(sass_dir).into_iter().filter_map(|e| e.ok()) {
+        if !entry.file_type().is_file() || !crate::is_sass_file(entry.path()) || crate::is_partial(entry.path()) {
+            continue;
+        }
+
+        let css = crate::compile_file(entry.path().to_path_buf(), format)?;
+        compiled.push((entry.path().strip_prefix(sass_dir).unwrap().to_path_buf(), css));
+    }
+
+    Ok(compiled)
+}
@@ -1,17 +1,113 @@
 use normpath::PathExt;
 
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::{CacheBust, ImporterFn, LineEndings, OutputLayout, OutputNameFn, OutputStyle, SassError, SassFunction, TransformFn, WatchEvent, WriterFn};
+
+/// Name of the file in `css_dir` recording which backend wrote its outputs
+const BACKEND_ID_FILE: &str = ".sass-backend";
+
+/// Name of the entrypoints manifest looked up in `sass_dir` by default
+const DEFAULT_ENTRYPOINTS_MANIFEST: &str = "entrypoints.toml";
+
+/// Returns `true` if `path` has one of `extensions`
+fn has_extension(path: &Path, extensions: &[String]) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| extensions.iter().any(|extension| extension == ext))
+}
 
 /// A Shared reference containing configuration data
+#[derive(Clone)]
 pub struct Context {
     pub sass_dir: PathBuf,
     pub css_dir: PathBuf,
+    /// Directory watched for changes triggering a reload (debug only), `sass_dir` by default
+    pub watch_dir: PathBuf,
     pub rsass_format: rsass::output::Format,
+    pub output_name: Arc<OutputNameFn>,
+    /// Writes a visible error banner in place of css that failed to compile (debug only)
+    pub error_overlay: bool,
+    /// Additional directories `@use`/`@import` are resolved from
+    pub load_paths: Vec<PathBuf>,
+    /// Post-processing steps run in order on each compiled output
+    pub transforms: Vec<Arc<TransformFn>>,
+    /// Skips the liftoff precompile of outputs newer than their source and its dependencies
+    pub skip_fresh: bool,
+    /// File system events in `sass_dir` that trigger a reload (debug only)
+    pub watch_events: Vec<WatchEvent>,
+    /// Sources (relative to `sass_dir`) kept in memory for inlining instead of written to `css_dir`
+    pub inline_patterns: Vec<glob::Pattern>,
+    /// Keeps every output in memory instead of writing to `css_dir`
+    pub in_memory: bool,
+    /// Recompiles on this interval regardless of file system events (debug only)
+    pub recompile_interval: Option<std::time::Duration>,
+    /// Skips writing outputs that compiled to nothing
+    pub skip_empty: bool,
+    /// Rust functions callable from sass
+    pub functions: Vec<SassFunction>,
+    /// Importers resolving urls before the filesystem
+    pub importers: Vec<Arc<ImporterFn>>,
+    /// Whether plain `.css` files in `sass_dir` are copied to `css_dir` unchanged
+    pub copy_css: bool,
+    /// Indentation per nesting level outputs are converted to, if any
+    pub indent: Option<String>,
+    /// Line endings outputs are converted to, if any
+    pub line_endings: Option<LineEndings>,
+    /// In-memory `(name, scss)` sources compiled instead of the contents of `sass_dir`
+    pub embedded_sources: Vec<(String, String)>,
+    /// Size in bytes above which an output is warned about, if any
+    pub max_css_bytes: Option<usize>,
+    /// Manifest listing the only sources that produce an output, used when it exists
+    pub entrypoints_manifest: PathBuf,
+    /// Logs how many lines of each changed output were added and removed
+    pub log_diff: bool,
+    /// Unix permissions of written outputs, if not the default ones (Unix only)
+    pub file_mode: Option<u32>,
+    /// Scss placed before every source, defining the `sass_vars`
+    pub prelude: String,
+    /// Time after which compiling a single file fails, if any
+    pub compile_timeout: Option<std::time::Duration>,
+    /// Logs a summary of every failed source at the end of a compile pass
+    pub error_summary: bool,
+    /// Prints the banner describing the setup at liftoff
+    pub banner_log: bool,
+    /// Directory keeping compiled css across restarts, keyed by the content hash of
+    /// the source and every file it loads
+    pub cache_dir: Option<PathBuf>,
+    /// File extensions of the sources in `sass_dir`, `scss` and `sass` by default
+    pub extensions: Vec<String>,
+    /// How links to outputs change along with their css, see `ContextManager::asset_url`
+    pub cache_bust: CacheBust,
+    /// Styles each source is compiled in, each written to its own output.
+    /// Empty to compile once with `rsass_format`
+    pub variants: Vec<OutputStyle>,
+    /// Logs failed sources, size budgets and diffs while compiling, turned off
+    /// e.g. to benchmark compiling alone. Failures are still recorded in `CompileStats`
+    pub compile_log: bool,
+    /// Persists outputs in place of writing them to `css_dir`
+    pub writer: Option<Arc<WriterFn>>,
+    /// How sources map to outputs, whether two of them may produce the same one
+    pub output_layout: OutputLayout,
+    /// Writes a Makefile style `.d` file listing the files each output depends on
+    pub dep_files: bool,
+    /// Compiles an output when it is requested through `mount_routes` rather than on
+    /// every change, only used in debug builds
+    pub lazy: bool,
+    /// How long browsers may keep css served from memory without revalidating it,
+    /// `None` to have them revalidate on every use
+    pub cache_max_age: Option<std::time::Duration>,
 }
 
 impl Context {
     /// Initializes the `Context` while checking for bad configuration
-    pub fn initialize(sass_dir: &Path, css_dir: &Path, rsass_format: rsass::output::Format) -> Option<Self> {
+    pub fn initialize(
+        sass_dir: &Path,
+        css_dir: &Path,
+        rsass_format: rsass::output::Format,
+        output_name: Arc<OutputNameFn>,
+    ) -> Option<Self> {
         let sass_dir_buf = match sass_dir.normalize() {
             Ok(dir) => dir.into_path_buf(),
             Err(e) => {
@@ -28,68 +124,837 @@ impl Context {
             }
         };
 
-        Some(Self { sass_dir: sass_dir_buf, css_dir: css_dir_buf, rsass_format: rsass_format })
+        Some(Self {
+            entrypoints_manifest: sass_dir_buf.join(DEFAULT_ENTRYPOINTS_MANIFEST),
+            watch_dir: sass_dir_buf.clone(),
+            sass_dir: sass_dir_buf,
+            css_dir: css_dir_buf,
+            rsass_format,
+            output_name,
+            error_overlay: false,
+            load_paths: Vec::new(),
+            transforms: Vec::new(),
+            skip_fresh: false,
+            watch_events: WatchEvent::DEFAULT.to_vec(),
+            inline_patterns: Vec::new(),
+            in_memory: false,
+            recompile_interval: None,
+            skip_empty: false,
+            functions: Vec::new(),
+            importers: Vec::new(),
+            copy_css: false,
+            indent: None,
+            line_endings: None,
+            embedded_sources: Vec::new(),
+            max_css_bytes: None,
+            log_diff: false,
+            file_mode: None,
+            prelude: String::new(),
+            compile_timeout: None,
+            error_summary: false,
+            banner_log: true,
+            lazy: false,
+            cache_dir: None,
+            dep_files: false,
+            output_layout: OutputLayout::Flat,
+            writer: None,
+            compile_log: true,
+            variants: Vec::new(),
+            cache_bust: CacheBust::None,
+            extensions: crate::SASS_EXTENSIONS.iter().map(|ext| ext.to_string()).collect(),
+            cache_max_age: None,
+        })
     }
-}
 
-pub use self::manager::ContextManager;
+    /// Creates a `Context` compiling `embedded_sources` instead of a `sass_dir`.
+    /// Nothing is read from or written to disk, so both directories are left empty
+    pub fn embedded(
+        embedded_sources: Vec<(String, String)>,
+        rsass_format: rsass::output::Format,
+        output_name: Arc<OutputNameFn>,
+    ) -> Self {
+        let mut ctx = Self::initialize(Path::new("."), Path::new("."), rsass_format, output_name)
+            .expect("current directory is not accessible");
 
-#[cfg(not(debug_assertions))]
-mod manager {
-    use std::ops::Deref;
-    use crate::Context;
+        ctx.sass_dir = PathBuf::new();
+        ctx.css_dir = PathBuf::new();
+        ctx.watch_dir = PathBuf::new();
+        ctx.in_memory = true;
+        ctx.embedded_sources = embedded_sources;
 
-    pub struct ContextManager(Context);
+        ctx
+    }
 
-    impl ContextManager {
-        pub fn new(ctx: Context) -> ContextManager {
-            ContextManager(ctx)
+    /// Returns `true` if this compiles embedded sources rather than `sass_dir`
+    pub fn is_embedded(&self) -> bool {
+        !self.embedded_sources.is_empty()
+    }
+
+    /// Returns the sources (relative to `sass_dir`) listed in `entrypoints_manifest`,
+    /// or `None` if there is no manifest and partials are told apart by their name.
+    ///
+    /// ```toml
+    /// entrypoints = ["main.scss", "admin/app.scss"]
+    /// ```
+    pub fn entrypoints(&self) -> Option<Vec<PathBuf>> {
+        use rocket::figment::providers::{Format, Toml};
+
+        if !self.entrypoints_manifest.is_file() {
+            return None;
         }
 
-        pub fn context<'a>(&'a self) -> impl Deref<Target=Context> + 'a {
-            &self.0
+        match rocket::figment::Figment::from(Toml::file(&self.entrypoints_manifest)).extract_inner("entrypoints") {
+            Ok(entrypoints) => Some(entrypoints),
+            Err(e) => {
+                rocket::error!("Invalid entrypoints manifest '{}': {}.", self.entrypoints_manifest.display(), e);
+                rocket::warn_!("Compiling every source that is not a partial instead.");
+                None
+            }
         }
+    }
 
-        pub fn is_reloading(&self) -> bool {
-            false
+    /// Returns a copy of this context compiling `sass_dir` into `css_dir` instead,
+    /// watching `sass_dir` and looking for the entrypoints manifest in it
+    pub fn for_dirs(&self, sass_dir: &Path, css_dir: &Path) -> Option<Self> {
+        let dirs = Self::initialize(sass_dir, css_dir, self.rsass_format, self.output_name.clone())?;
+
+        Some(Self {
+            sass_dir: dirs.sass_dir,
+            css_dir: dirs.css_dir,
+            watch_dir: dirs.watch_dir,
+            entrypoints_manifest: dirs.entrypoints_manifest,
+            ..self.clone()
+        })
+    }
+
+    /// Sets the directory watched for changes, which may be broader than `sass_dir`
+    /// when partials live higher up
+    pub fn set_watch_dir(&mut self, watch_dir: &Path) -> Option<()> {
+        match watch_dir.normalize() {
+            Ok(dir) => self.watch_dir = dir.into_path_buf(),
+            Err(e) => {
+                rocket::error!("Invalid sass watch directory '{}': {}.", watch_dir.display(), e);
+                return None;
+            }
+        }
+
+        Some(())
+    }
+
+    /// Returns `true` if files can be created in `css_dir`
+    pub fn is_css_dir_writable(&self) -> bool {
+        let probe = self.css_dir.join(".sass-write-probe");
+
+        std::fs::File::create(&probe).is_ok() && std::fs::remove_file(&probe).is_ok()
+    }
+
+    /// Returns `true` if `path` has one of the source `extensions`
+    pub fn is_source_file(&self, path: &Path) -> bool {
+        has_extension(path, &self.extensions)
+    }
+
+    /// Returns `true` if `source` (relative to `sass_dir`) is an inline-only source
+    pub fn is_inline(&self, source: &Path) -> bool {
+        self.inline_patterns.iter().any(|pattern| pattern.matches_path(source))
+    }
+
+    /// Identifies the compiler and output format producing the css.
+    /// Outputs written under a different identity are never considered up to date
+    pub fn backend_id(&self) -> String {
+        Self::backend_id_for(self.rsass_format)
+    }
+
+    /// Identifies the compiler producing css in `format`
+    fn backend_id_for(format: rsass::output::Format) -> String {
+        format!("rsass {} ({:?}, precision {})", crate::RSASS_VERSION, format.style, format.precision)
+    }
+
+    /// Returns the path (relative to `sass_dir`) each variant of `source` is named after,
+    /// along with the format it compiles with. Without `variants`, that is `source` itself
+    /// with `rsass_format`. Among several variants, the compressed one gets a `.min` suffix
+    pub fn variants(&self, source: &Path) -> Vec<(PathBuf, rsass::output::Format)> {
+        if self.variants.is_empty() {
+            return vec![(source.to_path_buf(), self.rsass_format)];
         }
 
-        // This method is just a quickfix to get rid of not-defined errors
-        pub fn compile_all_and_write(&self) {}
+        self.variants
+            .iter()
+            .map(|style| {
+                let format = rsass::output::Format {
+                    style: (*style).into(),
+                    ..self.rsass_format
+                };
+
+                let mut variant_path = source.to_path_buf();
+                if *style == OutputStyle::Compressed && self.variants.len() > 1 {
+                    let extension = source.extension().unwrap_or_default().to_string_lossy();
+                    variant_path.set_extension(format!("min.{}", extension));
+                }
+
+                (variant_path, format)
+            })
+            .collect()
+    }
+
+    /// Returns the source (relative to `sass_dir`) that `variant_path`, as returned by
+    /// `variants`, was derived from: `main.scss` for `main.min.scss`, or `variant_path`
+    /// itself if it is a source of its own
+    fn source_of_variant(&self, variant_path: &Path) -> PathBuf {
+        let stem = variant_path.file_stem().unwrap_or_default().to_string_lossy();
+        let stripped = match stem.strip_suffix(".min") {
+            Some(stem) if !self.sass_dir.join(variant_path).is_file() => stem,
+            _ => return variant_path.to_path_buf(),
+        };
+
+        let mut source = variant_path.with_file_name(stripped);
+        if let Some(extension) = variant_path.extension() {
+            source.set_extension(extension);
+        }
+
+        match self.variants(&source).iter().any(|(path, _)| path == variant_path) {
+            true => source,
+            false => variant_path.to_path_buf(),
+        }
+    }
+
+    /// Path of the file recording the `backend_id` the outputs in `css_dir` were written with
+    fn backend_id_path(&self) -> PathBuf {
+        self.css_dir.join(BACKEND_ID_FILE)
+    }
+
+    /// Returns `true` if the outputs in `css_dir` were written by the current `backend_id`
+    pub fn is_same_backend(&self) -> bool {
+        std::fs::read_to_string(self.backend_id_path()).is_ok_and(|id| id == self.backend_id())
+    }
+
+    /// Records the current `backend_id` as the one outputs in `css_dir` were written with
+    pub fn write_backend_id(&self) {
+        if let Err(e) = std::fs::write(self.backend_id_path(), self.backend_id()) {
+            rocket::warn_!("Failed to record the sass backend in '{}': {}", self.css_dir.display(), e);
+        }
+    }
+
+    /// Returns the most recent modification time of `source` and every sass file
+    /// it loads through `@use`, `@forward` and `@import`, see [`crate::dependencies_with_extensions`]
+    pub fn newest_dependency_mtime(&self, source: &Path) -> Option<std::time::SystemTime> {
+        std::iter::once(source.to_path_buf())
+            .chain(crate::dependencies_with_extensions(source, &self.load_paths, &self.extensions))
+            .filter_map(|path| std::fs::metadata(path).ok()?.modified().ok())
+            .max()
+    }
+
+    /// Compiles the sass file at `path` with the settings of this context.
+    /// With a `compile_timeout`, compiling happens on its own thread which is
+    /// abandoned (not stopped) once the timeout is over
+    pub fn compile_file(&self, path: &Path) -> Result<String, SassError> {
+        self.compile_file_as(path, self.rsass_format)
+    }
+
+    /// Same as `compile_file`, with `format` in place of `rsass_format`, e.g. for a variant
+    pub fn compile_file_as(&self, path: &Path, format: rsass::output::Format) -> Result<String, SassError> {
+        let Some(timeout) = self.compile_timeout else {
+            return crate::compile_file_with(
+                path.to_path_buf(),
+                format,
+                &self.load_paths,
+                &self.functions,
+                &self.importers,
+                &self.prelude,
+                &self.extensions,
+            );
+        };
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        let path_buf = path.to_path_buf();
+        let (load_paths, functions, importers, prelude, extensions) = (
+            self.load_paths.clone(),
+            self.functions.clone(),
+            self.importers.clone(),
+            self.prelude.clone(),
+            self.extensions.clone(),
+        );
+
+        std::thread::spawn(move || {
+            let _ = tx.send(crate::compile_file_with(path_buf, format, &load_paths, &functions, &importers, &prelude, &extensions));
+        });
+
+        match rx.recv_timeout(timeout) {
+            Ok(result) => result,
+            Err(std::sync::mpsc::RecvTimeoutError::Timeout) => Err(SassError::new(
+                path,
+                format!("Compiling took longer than {}s, giving up", timeout.as_secs()),
+            )),
+            Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => Err(SassError::new(path, "The compiler panicked")),
+        }
+    }
+
+    /// Same as `compile_file_as`, reusing the css in `cache_dir` compiled from the same
+    /// contents by the same backend and format, and storing it there otherwise
+    pub fn compile_file_cached(&self, path: &Path, format: rsass::output::Format) -> Result<String, SassError> {
+        let Some(cache_path) = self.cache_path(path, format) else {
+            return self.compile_file_as(path, format);
+        };
+
+        if let Ok(css) = std::fs::read_to_string(&cache_path) {
+            return Ok(css);
+        }
+
+        self.compile_into_cache(path, format, &cache_path)
+    }
+
+    /// Same as `compile_file_cached`, but compiles even if `cache_dir` holds css for
+    /// the same contents, replacing it
+    pub fn recompile_file_cached(&self, path: &Path, format: rsass::output::Format) -> Result<String, SassError> {
+        match self.cache_path(path, format) {
+            Some(cache_path) => self.compile_into_cache(path, format, &cache_path),
+            None => self.compile_file_as(path, format),
+        }
+    }
+
+    /// Compiles `path` and stores the css at `cache_path`
+    fn compile_into_cache(&self, path: &Path, format: rsass::output::Format, cache_path: &Path) -> Result<String, SassError> {
+        let css = self.compile_file_as(path, format)?;
+
+        let written = cache_path.parent().map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(cache_path, &css));
+        if let Err(e) = written {
+            rocket::warn_!("Failed to cache css in '{}': {}", cache_path.display(), e);
+        }
+
+        Ok(css)
+    }
+
+    /// Returns the file in `cache_dir` caching the css of `source`, named after the
+    /// hash of its contents and every file it loads. `None` without a `cache_dir`
+    /// or if any of them can't be read
+    fn cache_path(&self, source: &Path, format: rsass::output::Format) -> Option<PathBuf> {
+        use std::hash::Hasher;
+
+        let cache_dir = self.cache_dir.as_ref()?;
+        let mut hasher = crate::StableHasher::default();
+
+        for part in [Self::backend_id_for(format).as_bytes(), self.prelude.as_bytes()] {
+            hasher.write(&(part.len() as u64).to_le_bytes());
+            hasher.write(part);
+        }
+
+        for path in std::iter::once(source.to_path_buf()).chain(crate::dependencies_with_extensions(source, &self.load_paths, &self.extensions)) {
+            let contents = std::fs::read(&path).ok()?;
+            let path = path.to_string_lossy();
+
+            for part in [path.as_bytes(), contents.as_slice()] {
+                hasher.write(&(part.len() as u64).to_le_bytes());
+                hasher.write(part);
+            }
+        }
+
+        Some(cache_dir.join(format!("{:016x}.css", hasher.finish())))
+    }
+
+    /// Runs `transforms` in order on the css compiled from `source`.
+    /// Errors returned without a path are attributed to `source`
+    pub fn apply_transforms(&self, source: &Path, css: String) -> Result<String, SassError> {
+        self.transforms.iter().try_fold(css, |css, transform| {
+            transform(&css).map_err(|mut e| {
+                if e.path.as_os_str().is_empty() {
+                    e.path = source.to_path_buf();
+                }
+
+                e
+            })
+        })
+    }
+
+    /// Rewrites the indentation of compiled `css` with `indent` and its line endings
+    /// with `line_endings`, leaving either alone when unset. Applied to every compiled
+    /// output before it's written or compared with the one in `css_dir`
+    pub fn format_output(&self, css: String) -> String {
+        let css = match &self.indent {
+            Some(indent) => crate::reindent(&css, indent),
+            None => css,
+        };
+
+        match self.line_endings {
+            Some(line_endings) => line_endings.apply(&css),
+            None => css,
+        }
+    }
+
+    /// Resolves `paths` against the cargo workspace root and appends them to `load_paths`
+    pub fn add_workspace_paths(&mut self, paths: &[PathBuf]) -> Option<()> {
+        let workspace_root = find_workspace_root();
+        let paths: Vec<PathBuf> = paths.iter().map(|path| workspace_root.join(path)).collect();
+
+        self.add_load_paths(&paths)
+    }
+
+    /// Appends `paths` to `load_paths`, made absolute.
+    /// Relative paths are resolved against the current directory
+    pub fn add_load_paths(&mut self, paths: &[PathBuf]) -> Option<()> {
+        for path in paths {
+            match path.normalize() {
+                Ok(dir) => self.load_paths.push(dir.into_path_buf()),
+                Err(e) => {
+                    rocket::error!("Invalid sass load path '{}': {}.", path.display(), e);
+                    return None;
+                }
+            }
+        }
+
+        Some(())
+    }
+}
+
+/// Finds the root of the cargo workspace the app is run from.
+/// Walks up from `CARGO_MANIFEST_DIR` (or the current directory) looking for a
+/// `Cargo.toml` declaring `[workspace]`, falling back to the starting directory
+fn find_workspace_root() -> PathBuf {
+    let start = std::env::var_os("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| std::env::current_dir().unwrap());
+
+    start
+        .ancestors()
+        .find(|dir| {
+            std::fs::read_to_string(dir.join("Cargo.toml"))
+                .map(|manifest| manifest.lines().any(|line| line.trim() == "[workspace]"))
+                .unwrap_or(false)
+        })
+        .unwrap_or(&start)
+        .to_path_buf()
+}
+
+pub use self::manager::ContextManager;
+
+/// Registry of the `ContextManager`s of named `SassFairing` instances
+#[derive(Default)]
+pub struct NamedContextManagers(std::sync::RwLock<std::collections::HashMap<String, Arc<ContextManager>>>);
+
+impl NamedContextManagers {
+    /// Returns the `ContextManager` of the instance named `name`
+    pub fn get(&self, name: &str) -> Option<Arc<ContextManager>> {
+        self.0.read().unwrap().get(name).cloned()
+    }
+
+    pub(crate) fn insert(&self, name: &str, ctx_manager: ContextManager) {
+        self.0.write().unwrap().insert(name.to_string(), Arc::new(ctx_manager));
+    }
+}
+
+/// Summary of a compile pass over `sass_dir`
+#[derive(Debug, Clone, Default)]
+pub struct CompileStats {
+    /// Number of sources compiled successfully
+    pub compiled: usize,
+    /// Number of sources that failed to compile
+    pub failed: usize,
+    /// Number of sources skipped because their output was up to date
+    pub skipped: usize,
+    /// Outputs whose css differs from what was previously written, relative to `css_dir`
+    pub changed: Vec<PathBuf>,
+    /// Size in bytes of every output, relative to `css_dir`
+    pub output_sizes: Vec<(PathBuf, usize)>,
+    /// Errors of the sources that failed to compile
+    pub errors: Vec<crate::SassError>,
+    /// Time spent compiling
+    pub duration: std::time::Duration,
+    /// Whether the pass was left to a compile already in progress, which runs once more
+    /// after finishing instead. Everything else is then empty
+    pub deferred: bool,
+}
+
+impl CompileStats {
+    /// Adds the results of `other`, e.g. the pass over another directory pair
+    pub fn merge(&mut self, other: CompileStats) {
+        self.compiled += other.compiled;
+        self.failed += other.failed;
+        self.skipped += other.skipped;
+        self.changed.extend(other.changed);
+        self.output_sizes.extend(other.output_sizes);
+        self.errors.extend(other.errors);
+        self.duration += other.duration;
+        self.deferred &= other.deferred;
+    }
+
+    /// Total size in bytes of all outputs
+    pub fn total_bytes(&self) -> usize {
+        self.output_sizes.iter().map(|(_, size)| size).sum()
+    }
+}
+
+/// A completed compile pass, see `ContextManager::compile_events`
+#[derive(Debug, Clone)]
+pub struct CompileEvent {
+    /// Outputs whose css changed, relative to `css_dir`
+    pub changed: Vec<PathBuf>,
+    /// Errors of the sources that failed to compile
+    pub errors: Vec<crate::SassError>,
+}
+
+impl CompileEvent {
+    /// Returns `true` if every source compiled
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl From<&CompileStats> for CompileEvent {
+    fn from(stats: &CompileStats) -> Self {
+        Self {
+            changed: stats.changed.clone(),
+            errors: stats.errors.clone(),
+        }
     }
 }
 
-#[cfg(debug_assertions)]
 mod manager {
-    use std::sync::{RwLock, Mutex, mpsc};
+    use std::sync::{Arc, Mutex, RwLock, TryLockError};
+    #[cfg(all(feature = "watch", debug_assertions))]
+    use std::sync::atomic::{AtomicBool, Ordering};
+    #[cfg(all(feature = "watch", debug_assertions))]
+    use std::sync::mpsc;
     use std::collections::HashMap;
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
     use std::fs;
+    use std::time::SystemTime;
 
     use std::io::Write;
 
+    #[cfg(all(feature = "watch", debug_assertions))]
     use notify::{raw_watcher, RawEvent, RecommendedWatcher, RecursiveMode, Watcher};
     use walkdir::WalkDir;
 
-    use super::Context;
+    use rocket::futures::Stream;
+    use rocket::tokio::sync::broadcast;
+
+    use super::{CompileEvent, CompileStats, Context};
+    use crate::{CacheBust, SassError};
+
+    /// Number of compile events kept for a subscriber lagging behind
+    const EVENT_CAPACITY: usize = 16;
+
+    fn is_css_file(path: &Path) -> bool {
+        path.extension().is_some_and(|ext| ext == "css")
+    }
+
+    /// Runs `format_output` on the css compiled from `source`. Plain css files
+    /// copied with `copy_css` are written exactly as they are in `sass_dir`
+    fn format_compiled(context: &Context, source: &Path, css: String) -> String {
+        if context.copy_css && is_css_file(source) {
+            css
+        } else {
+            context.format_output(css)
+        }
+    }
+
+    /// Whether `path` exists and was modified no earlier than `than`
+    fn is_newer(path: &Path, than: &Path) -> bool {
+        let mtime = |path: &Path| fs::metadata(path).and_then(|meta| meta.modified());
+
+        match (mtime(path), mtime(than)) {
+            (Ok(mtime), Ok(than)) => mtime >= than,
+            _ => false,
+        }
+    }
+
+    /// Sources a compile pass leaves alone
+    #[derive(Debug, Clone, Copy)]
+    enum Skip<'a> {
+        /// Compiles every source
+        Nothing,
+        /// Compiles every source, even one whose css is in `cache_dir`
+        Uncached,
+        /// Sources whose output is newer than them and every file they load
+        Fresh,
+        /// Sources that, along with every file they load, were not modified after this time
+        UnchangedSince(SystemTime),
+        /// Sources other than these, relative to `sass_dir`
+        Others(&'a [PathBuf]),
+    }
+
+    /// Whether `path` exists and was last modified no later than `since`
+    fn is_unchanged_since(path: &Path, since: SystemTime) -> bool {
+        fs::metadata(path)
+            .and_then(|meta| meta.modified())
+            .is_ok_and(|mtime| mtime <= since)
+    }
+
+    /// Returns the files in `sass_dir` producing an output, relative to it:
+    /// the entrypoints, and plain css files when they are copied
+    /// Returns the outputs (relative to `css_dir`) of `source`, relative to `sass_dir`
+    /// or inside it, given the `entrypoints` read from the manifest
+    fn outputs_of(context: &Context, entrypoints: &Option<Vec<PathBuf>>, source: &Path) -> Vec<PathBuf> {
+        let relative = source.strip_prefix(&context.sass_dir).unwrap_or(source);
+
+        let is_source = if context.is_embedded() {
+            context.embedded_sources.iter().any(|(name, _)| Path::new(name) == relative)
+        } else if relative.is_absolute() || !context.sass_dir.join(relative).is_file() {
+            false
+        } else if context.is_source_file(relative) {
+            match entrypoints {
+                Some(entrypoints) => entrypoints.iter().any(|entrypoint| entrypoint == relative),
+                None => !crate::is_partial(relative),
+            }
+        } else {
+            context.copy_css && is_css_file(relative)
+        };
+
+        if !is_source {
+            return Vec::new();
+        }
+
+        match !context.is_embedded() && context.is_source_file(relative) {
+            true => context.variants(relative).into_iter().map(|(variant_path, _)| (context.output_name)(&variant_path)).collect(),
+            false => vec![(context.output_name)(relative)],
+        }
+    }
 
-    /// Manages the `Context`
+    /// Returns the canonical directory of the entrypoints manifest, if that directory exists
+    #[cfg(all(feature = "watch", debug_assertions))]
+    fn manifest_dir(context: &Context) -> Option<PathBuf> {
+        match context.entrypoints_manifest.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.canonicalize().ok(),
+            _ => Path::new(".").canonicalize().ok(),
+        }
+    }
+
+    fn source_files(context: &Context) -> Vec<PathBuf> {
+        let entrypoints = context.entrypoints();
+        let mut sources = Vec::new();
+
+        for entry in WalkDir::new(&context.sass_dir).into_iter().filter_map(|e| e.ok()) {
+            let Ok(relative) = entry.path().strip_prefix(&context.sass_dir) else {
+                continue;
+            };
+
+            let is_source = entry.file_type().is_file() && if context.is_source_file(relative) {
+                match &entrypoints {
+                    Some(entrypoints) => entrypoints.iter().any(|entrypoint| entrypoint == relative),
+                    None => !crate::is_partial(relative),
+                }
+            } else {
+                context.copy_css && is_css_file(relative)
+            };
+
+            if is_source {
+                sources.push(relative.to_path_buf());
+            }
+        }
+
+        sources
+    }
+
+    /// Returns each output (relative to `css_dir`) produced by more than one source,
+    /// along with those sources (relative to `sass_dir`)
+    fn output_collisions(context: &Context) -> Vec<(PathBuf, Vec<PathBuf>)> {
+        let mut outputs: std::collections::BTreeMap<PathBuf, Vec<PathBuf>> = Default::default();
+
+        for source in source_files(context) {
+            outputs.entry((context.output_name)(&source)).or_default().push(source);
+        }
+
+        outputs.into_iter().filter(|(_, sources)| sources.len() > 1).collect()
+    }
+
+    /// Writes `<output>.d` next to `output`, in the Makefile format listing `source`
+    /// and every file it loads, e.g. `main.css: main.scss _colors.scss`
+    fn write_dep_file(context: &Context, output: &Path, source: &Path) {
+        // Make treats spaces as separators
+        let escape = |path: &Path| path.display().to_string().replace(' ', "\\ ");
+
+        let deps: Vec<String> = std::iter::once(source.to_path_buf())
+            .chain(crate::dependencies_with_extensions(source, &context.load_paths, &context.extensions))
+            .map(|path| escape(&path))
+            .collect();
+
+        let mut dep_file_path = output.as_os_str().to_owned();
+        dep_file_path.push(".d");
+
+        let contents = format!("{}: {}\n", escape(output), deps.join(" "));
+        if let Err(e) = fs::write(&dep_file_path, contents) {
+            rocket::warn_!("Failed to write dependency file '{}': {}", Path::new(&dep_file_path).display(), e);
+        }
+    }
+
+    /// Creates (or truncates) the output file at `path`, with the permissions `mode` if set.
+    /// The mode is set again once open, as creating the file is subject to the umask
+    /// and an existing file keeps its permissions
+    #[cfg(unix)]
+    fn create_output(path: &Path, mode: Option<u32>) -> std::io::Result<fs::File> {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+
+        let Some(mode) = mode else {
+            return options.open(path);
+        };
+
+        let file = options.mode(mode).open(path)?;
+        file.set_permissions(fs::Permissions::from_mode(mode))?;
+
+        Ok(file)
+    }
+
+    /// Creates (or truncates) the output file at `path`. Permissions are Unix only
+    #[cfg(not(unix))]
+    fn create_output(path: &Path, _mode: Option<u32>) -> std::io::Result<fs::File> {
+        fs::File::create(path)
+    }
+
+    /// Logs every error of a compile pass at once, grouped by source, after the
+    /// errors interleaved with the other logs. Colors follow rocket's tty detection
+    fn log_error_summary(context: &Context, stats: &CompileStats) {
+        use rocket::yansi::Paint;
+
+        if stats.errors.is_empty() {
+            return;
+        }
+
+        rocket::error!(
+            "{} of {} sass sources failed to compile:",
+            stats.failed.red().bold(),
+            stats.compiled + stats.failed
+        );
+
+        for e in &stats.errors {
+            let path = e.path.strip_prefix(&context.sass_dir).unwrap_or(&e.path);
+
+            rocket::error_!("{}", Paint::new(path.display()).bold());
+            for line in e.message.lines() {
+                rocket::error_!("  {}", Paint::red(line));
+            }
+        }
+    }
+
+    /// Logs how many lines of `output_name` were added and removed since `previous`.
+    /// Lines are compared as a whole, regardless of where they moved to
+    fn log_diff(output_name: &Path, previous: Option<&str>, current: &str) {
+        let Some(previous) = previous else {
+            rocket::info_!("{}: new file, {} lines", output_name.display(), current.lines().count());
+            return;
+        };
+
+        let mut counts: HashMap<&str, isize> = HashMap::new();
+        for line in previous.lines() {
+            *counts.entry(line).or_default() -= 1;
+        }
+        for line in current.lines() {
+            *counts.entry(line).or_default() += 1;
+        }
+
+        let added: isize = counts.values().filter(|count| **count > 0).sum();
+        let removed: isize = counts.values().filter(|count| **count < 0).map(|count| -count).sum();
+
+        rocket::info_!("{}: +{} -{} lines", output_name.display(), added, removed);
+    }
+
+    /// Css compiled by a pass, along with the source it was compiled from
+    struct CompiledOutput {
+        /// Path of the source, relative to `sass_dir`
+        source: PathBuf,
+        /// Path the output is named after, relative to `sass_dir`: the source itself or
+        /// one of its `sass_variants`, e.g. `main.min.scss`
+        variant: PathBuf,
+        css: String,
+    }
+
+    /// Keys compiled css by the variant path it is named after, as `compile_all` returns it
+    fn keyed_by_variant(compiled: Vec<CompiledOutput>) -> HashMap<PathBuf, String> {
+        compiled.into_iter().map(|output| (output.variant, output.css)).collect()
+    }
+
+    /// A writing pass over every source, ordered by how much it redoes
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    enum Pass {
+        /// See `compile_stale_and_write`
+        Stale,
+        /// See `compile_all_and_write`
+        All,
+        /// See `recompile_all_force`
+        Force,
+    }
+
+    /// Manages the `Context`.
+    /// Compiling is available in every build, watching `sass_dir` only in debug builds
+    /// with the `watch` feature.
+    /// Clones share the same state
+    #[derive(Clone)]
     pub struct ContextManager{
-        context: RwLock<Context>,
-        watcher: Option<(RecommendedWatcher, Mutex<mpsc::Receiver<RawEvent>>)>
+        context: Arc<RwLock<Context>>,
+        /// Error of the last failed compile of each output, keyed by path relative to `css_dir`
+        last_errors: Arc<Mutex<HashMap<PathBuf, SassError>>>,
+        /// Compiled css kept in memory instead of written to `css_dir`,
+        /// keyed by output path relative to `css_dir`
+        memory_css: Arc<RwLock<HashMap<PathBuf, String>>>,
+        /// Held while a compile pass writes its outputs, so only one runs at a time
+        compile_lock: Arc<Mutex<()>>,
+        /// Strongest pass requested since the holder of `compile_lock` last started one
+        compile_pending: Arc<Mutex<Option<Pass>>>,
+        /// Sends an event after every compile pass writing its outputs
+        events: broadcast::Sender<CompileEvent>,
+        /// Managers of the other `(sass_dir, css_dir)` pairs, compiled along with this one
+        linked: Vec<ContextManager>,
+        #[cfg(all(feature = "watch", debug_assertions))]
+        watcher: Option<Arc<(RecommendedWatcher, Mutex<mpsc::Receiver<RawEvent>>)>>,
+        /// Set once the watcher stopped sending events, so that is only reported once
+        #[cfg(all(feature = "watch", debug_assertions))]
+        watcher_lost: Arc<AtomicBool>,
     }
 
     impl ContextManager {
         pub fn new(ctx: Context) -> Self {
+            // Embedded sources have nothing on disk to watch, lazy outputs compile on request
+            #[cfg(all(feature = "watch", debug_assertions))]
+            let watcher = if ctx.is_embedded() || ctx.lazy { None } else { Self::watch(&ctx) };
+
+            Self {
+                context: Arc::new(RwLock::new(ctx)),
+                last_errors: Arc::new(Mutex::new(HashMap::new())),
+                memory_css: Arc::new(RwLock::new(HashMap::new())),
+                compile_lock: Arc::new(Mutex::new(())),
+                compile_pending: Arc::new(Mutex::new(None)),
+                events: broadcast::channel(EVENT_CAPACITY).0,
+                linked: Vec::new(),
+                #[cfg(all(feature = "watch", debug_assertions))]
+                watcher,
+                #[cfg(all(feature = "watch", debug_assertions))]
+                watcher_lost: Arc::new(AtomicBool::new(false)),
+            }
+        }
+
+        /// Adds the manager of another `(sass_dir, css_dir)` pair, whose passes then run
+        /// along with the writing passes and reloads of this one
+        pub fn link(mut self, other: ContextManager) -> Self {
+            self.linked.push(other);
+            self
+        }
+
+        /// Returns the managers of the other `(sass_dir, css_dir)` pairs
+        pub fn linked(&self) -> &[ContextManager] {
+            &self.linked
+        }
+
+        /// Starts watching `watch_dir` for changes
+        #[cfg(all(feature = "watch", debug_assertions))]
+        fn watch(ctx: &Context) -> Option<Arc<(RecommendedWatcher, Mutex<mpsc::Receiver<RawEvent>>)>> {
             let (tx, rx) = mpsc::channel();
             let watcher = raw_watcher(tx).and_then(|mut watcher| {
-                watcher.watch(ctx.sass_dir.canonicalize()?, RecursiveMode::Recursive)?;
+                let watch_dir = ctx.watch_dir.canonicalize()?;
+                watcher.watch(&watch_dir, RecursiveMode::Recursive)?;
+
+                // Edits to the entrypoints manifest change what gets compiled
+                if let Some(manifest_dir) = manifest_dir(ctx) {
+                    if !manifest_dir.starts_with(&watch_dir) {
+                        watcher.watch(manifest_dir, RecursiveMode::NonRecursive)?;
+                    }
+                }
 
                 Ok(watcher)
             });
 
-            let watcher = match watcher {
-                Ok(watcher) => Some((watcher, Mutex::new(rx))),
+            match watcher {
+                Ok(watcher) => Some(Arc::new((watcher, Mutex::new(rx)))),
                 Err(e) => {
                     rocket::warn!("Failed to enable live sass compiling: {}", e);
                     rocket::debug_!("Reload error: {:?}", e);
@@ -97,9 +962,13 @@ mod manager {
 
                     None
                 }
-            };
+            }
+        }
 
-            Self { context: RwLock::new(ctx), watcher }
+        /// Returns the absolute directories `@use`/`@import` urls are resolved from, after
+        /// the directory of the importing file, in the order they are tried
+        pub fn load_paths(&self) -> Vec<PathBuf> {
+            self.context().load_paths.clone()
         }
 
         /// Returns `Context` as read only
@@ -112,76 +981,961 @@ mod manager {
             self.context.write().unwrap()
         } 
 
-        /// Compiles all files in `sass_dir`
-        pub fn compile_all(&self) -> Result<HashMap<String, String>, ()> {
-            let mut compiled: HashMap<String, String> = HashMap::new();
-            let sass_dir = &*self.context().sass_dir;
-            let rsass_format = *&self.context().rsass_format;
+        /// Compiles all files in `sass_dir`, keyed by their path relative to `sass_dir`.
+        /// Only covers this pair, the linked ones are compiled through `linked`
+        pub fn compile_all(&self) -> (HashMap<PathBuf, String>, CompileStats) {
+            let (compiled, stats) = self.compile_sources(Skip::Nothing);
+
+            (keyed_by_variant(compiled), stats)
+        }
+
+        /// Compiles only the files in `sass_dir` whose output is missing or older
+        /// than the file or any sass file it loads. Only covers this pair, like `compile_all`
+        pub fn compile_stale(&self) -> (HashMap<PathBuf, String>, CompileStats) {
+            let (compiled, stats) = self.compile_sources(Skip::Fresh);
+
+            (keyed_by_variant(compiled), stats)
+        }
+
+        fn compile_sources(&self, skip: Skip) -> (Vec<CompiledOutput>, CompileStats) {
+            let mut compiled: Vec<CompiledOutput> = Vec::new();
+            let mut stats = CompileStats::default();
+            let started = std::time::Instant::now();
+            let context = self.context();
+            let sass_dir = &*context.sass_dir;
+            let mut sources_found = 0;
+
+            if context.is_embedded() {
+                for (name, scss) in &context.embedded_sources {
+                    let result = crate::compile_string_with(name, scss, context.rsass_format, &context.load_paths, &context.functions, &context.importers, &context.prelude)
+                        .and_then(|css| context.apply_transforms(Path::new(name), css));
+
+                    match result {
+                        Ok(css) => {
+                            compiled.push(CompiledOutput { source: PathBuf::from(name), variant: PathBuf::from(name), css });
+                            stats.compiled += 1;
+                        }
+                        Err(e) => {
+                            if context.compile_log {
+                                rocket::error!("Failed to compile embedded source '{}'", name);
+                                rocket::error!("Sass error: {}", e.message);
+                            }
+                            stats.failed += 1;
+                            stats.errors.push(e);
+                        }
+                    }
+                }
+
+                if context.error_summary && context.compile_log {
+                    log_error_summary(&context, &stats);
+                }
+
+                stats.duration = started.elapsed();
+                return (compiled, stats);
+            }
+
+            // Fail the whole pass rather than have one output overwrite another
+            if context.output_layout == crate::OutputLayout::FlatStrict {
+                let collisions = output_collisions(&context);
+
+                for (output, sources) in &collisions {
+                    let sources = sources.iter().map(|source| format!("'{}'", source.display())).collect::<Vec<_>>();
+                    let message = format!("Sources {} all produce '{}'", sources.join(", "), output.display());
+
+                    if context.compile_log {
+                        rocket::error!("{}", message);
+                    }
+                    stats.failed += 1;
+                    stats.errors.push(SassError::new(sass_dir, message));
+                }
+
+                if !collisions.is_empty() {
+                    stats.duration = started.elapsed();
+                    return (compiled, stats);
+                }
+            }
+
+            // Nothing is up to date if it was written by another backend
+            let check_fresh = matches!(skip, Skip::Fresh) && context.is_same_backend();
+
+            // Read on every pass so that edits to the manifest apply on the next reload
+            let entrypoints = context.entrypoints();
+            let is_entrypoint = |path: &Path| match &entrypoints {
+                Some(entrypoints) => entrypoints.iter().any(|entrypoint| path.strip_prefix(sass_dir) == Ok(entrypoint)),
+                None => !crate::is_partial(path),
+            };
 
             for entry in WalkDir::new(sass_dir).into_iter().filter_map(|e| e.ok()) {
-                if entry.metadata().unwrap().is_file() {
-                    let file_name = entry.path().file_name().unwrap().to_str().unwrap().to_string();
-                    let result = match crate::compile_file(entry.into_path(), rsass_format) {
-                        Ok(result) => result,
+                if context.copy_css && entry.metadata().unwrap().is_file() && is_css_file(entry.path()) {
+                    let relative_path = entry.path().strip_prefix(sass_dir).unwrap().to_path_buf();
+                    let output_path = context.css_dir.join((context.output_name)(&relative_path));
+
+                    // Nothing to copy when sass_dir and css_dir overlap
+                    if output_path == entry.path() {
+                        continue;
+                    }
+
+                    sources_found += 1;
+
+                    let unchanged = match skip {
+                        Skip::Nothing | Skip::Uncached => false,
+                        Skip::Fresh => is_newer(&output_path, entry.path()),
+                        Skip::UnchangedSince(since) => is_unchanged_since(entry.path(), since),
+                        Skip::Others(sources) => !sources.contains(&relative_path),
+                    };
+
+                    if unchanged {
+                        stats.skipped += 1;
+                        continue;
+                    }
+
+                    match fs::read_to_string(entry.path()) {
+                        Ok(css) => {
+                            compiled.push(CompiledOutput { source: relative_path.clone(), variant: relative_path, css });
+                            stats.compiled += 1;
+                        }
                         Err(e) => {
-                            rocket::error!("Failed to compile file '{}'", file_name);
-                            rocket::error!("Sass error: {:?}", e);
+                            if context.compile_log {
+                                rocket::error!("Failed to read css file '{}': {}", relative_path.display(), e);
+                            }
+                            stats.failed += 1;
+                            stats.errors.push(SassError::new(entry.path(), e.to_string()));
+                        }
+                    }
+                    continue;
+                }
+
+                if entry.metadata().unwrap().is_file()
+                    && context.is_source_file(entry.path())
+                    && is_entrypoint(entry.path())
+                {
+                    sources_found += 1;
+
+                    let relative_path = entry.path().strip_prefix(sass_dir).unwrap().to_path_buf();
+                    let variants = context.variants(&relative_path);
+
+                    if let Some(newest_source) = check_fresh.then(|| context.newest_dependency_mtime(entry.path())).flatten() {
+                        let is_fresh = |variant_path: &PathBuf| {
+                            let output = context.css_dir.join((context.output_name)(variant_path));
+                            fs::metadata(output).and_then(|meta| meta.modified()).is_ok_and(|mtime| mtime >= newest_source)
+                        };
 
-                            break;
+                        if variants.iter().all(|(variant_path, _)| is_fresh(variant_path)) {
+                            stats.skipped += 1;
+                            continue;
                         }
-                    };
+                    }
+
+                    if let Skip::UnchangedSince(since) = skip {
+                        if context.newest_dependency_mtime(entry.path()).is_some_and(|mtime| mtime <= since) {
+                            stats.skipped += 1;
+                            continue;
+                        }
+                    }
+
+                    if let Skip::Others(sources) = skip {
+                        if !sources.contains(&relative_path) {
+                            stats.skipped += 1;
+                            continue;
+                        }
+                    }
+
+                    for (variant_path, format) in variants {
+                        let output = (context.output_name)(&variant_path);
+                        let result = match skip {
+                            Skip::Uncached => context.recompile_file_cached(entry.path(), format),
+                            _ => context.compile_file_cached(entry.path(), format),
+                        };
+                        let result = result.and_then(|css| context.apply_transforms(entry.path(), css));
+
+                        let result = match result {
+                            Ok(result) => result,
+                            Err(e) => {
+                                if context.compile_log {
+                                    rocket::error!("Failed to compile file '{}'", relative_path.display());
+                                    rocket::error!("Sass error: {}", e.message);
+                                }
+                                stats.failed += 1;
 
-                    compiled.insert(file_name, result);
+                                #[cfg(debug_assertions)]
+                                if context.error_overlay {
+                                    compiled.push(CompiledOutput {
+                                        source: relative_path.clone(),
+                                        variant: variant_path,
+                                        css: e.overlay_css(),
+                                    });
+                                }
+
+                                self.last_errors.lock().unwrap().insert(output, e.clone());
+                                stats.errors.push(e);
+                                continue;
+                            }
+                        };
+
+                        self.last_errors.lock().unwrap().remove(&output);
+                        compiled.push(CompiledOutput { source: relative_path.clone(), variant: variant_path, css: result });
+                        stats.compiled += 1;
+                    }
                 }
             }
 
-            Ok(compiled)
+            if sources_found == 0 && context.compile_log {
+                rocket::warn_!("No sass files found in '{}'.", sass_dir.display());
+            }
+
+            if context.error_summary && context.compile_log {
+                log_error_summary(&context, &stats);
+            }
+
+            stats.duration = started.elapsed();
+
+            (compiled, stats)
+        }
+
+        /// Writes all compiled files to `css_dir`, naming them with `Context.output_name`.
+        /// Inline-only sources, or every source in memory mode, are kept in memory
+        /// instead, see `compiled_css`. Takes the css of this pair, as `compile_all` returns it
+        pub fn write_compiled(&self, compiled_files: HashMap<PathBuf, String>) -> Vec<PathBuf> {
+            let compiled_files = {
+                let context = self.context();
+                compiled_files
+                    .into_iter()
+                    .map(|(variant, css)| CompiledOutput { source: context.source_of_variant(&variant), variant, css })
+                    .collect()
+            };
+
+            let mut stats = CompileStats::default();
+            self.write_compiled_into(compiled_files, &mut stats);
+
+            stats.changed
         }
 
-        /// Writes all compiled files to `css_dir`
-        pub fn write_compiled(&self, compiled_files: HashMap<String, String>) {
-            let css_dir = &*self.context().css_dir;
+        /// Same as `write_compiled`, recording the changed outputs and the size
+        /// of every output in `stats`
+        fn write_compiled_into(&self, compiled_files: Vec<CompiledOutput>, stats: &mut CompileStats) {
+            let context = self.context();
+            let css_dir = &*context.css_dir;
+            let mut changed = Vec::new();
 
-            for (sass_file_name, compiled) in compiled_files {
-                let mut sass_file_name_path = PathBuf::new();
+            for CompiledOutput { source, variant, css } in compiled_files {
+                let output_name = (context.output_name)(&variant);
+                let compiled = format_compiled(&context, &source, css);
 
-                sass_file_name_path.push(sass_file_name);
-                sass_file_name_path.set_extension("css");
+                if let Some(max_bytes) = context.max_css_bytes.filter(|max| compiled.len() > *max && context.compile_log) {
+                    rocket::warn_!(
+                        "Css file '{}' is {} bytes, over the budget of {} bytes.",
+                        output_name.display(),
+                        compiled.len(),
+                        max_bytes
+                    );
+                }
+                stats.output_sizes.push((output_name.clone(), compiled.len()));
+
+                // The hashed copy goes along with the plain output, which freshness checks still use
+                let hashed_name = (context.cache_bust == CacheBust::Filename)
+                    .then(|| crate::hashed_name(&output_name, &crate::short_hash(&compiled)));
 
-                let css_file_path = css_dir.join(sass_file_name_path);
+                if context.in_memory || context.is_inline(&source) {
+                    let previous = self.memory_css.write().unwrap().insert(output_name.clone(), compiled.clone());
 
-                let mut file = fs::File::create(&css_file_path)
-                    .expect(format!("Failed to create css file: '{:?}'", css_file_path).as_str());
+                    if let Some(hashed_name) = hashed_name {
+                        let mut memory_css = self.memory_css.write().unwrap();
+                        if let Some(previous) = previous.as_ref().filter(|previous| **previous != compiled) {
+                            memory_css.remove(&crate::hashed_name(&output_name, &crate::short_hash(previous)));
+                        }
+                        memory_css.insert(hashed_name, compiled.clone());
+                    }
+
+                    if previous.as_ref() != Some(&compiled) {
+                        if context.log_diff && context.compile_log {
+                            log_diff(&output_name, previous.as_deref(), &compiled);
+                        }
+                        changed.push(output_name);
+                    }
+                    continue;
+                }
+
+                if context.skip_empty && compiled.trim().is_empty() {
+                    continue;
+                }
+
+                // Nothing to compare against, every output handed over counts as changed
+                if let Some(writer) = &context.writer {
+                    let written = writer(&output_name, &compiled)
+                        .and_then(|_| hashed_name.map_or(Ok(()), |hashed_name| writer(&hashed_name, &compiled)));
+
+                    match written {
+                        Ok(()) => changed.push(output_name),
+                        Err(e) => {
+                            if context.compile_log {
+                                rocket::error!("Failed to write css file '{}'", output_name.display());
+                                rocket::error!("Sass error: {}", e.message);
+                            }
+                            stats.failed += 1;
+                            stats.errors.push(e);
+                        }
+                    }
+                    continue;
+                }
+
+                let css_file_path = css_dir.join(&output_name);
+                let previous = fs::read_to_string(&css_file_path).ok();
+                if previous.as_ref() != Some(&compiled) {
+                    if context.log_diff && context.compile_log {
+                        log_diff(&output_name, previous.as_deref(), &compiled);
+                    }
+                    changed.push(output_name.clone());
+                }
+
+                if let Some(parent) = css_file_path.parent() {
+                    fs::create_dir_all(parent)
+                        .unwrap_or_else(|_| panic!("Failed to create css directory: '{:?}'", parent));
+                }
+
+                let mut file = create_output(&css_file_path, context.file_mode)
+                    .unwrap_or_else(|_| panic!("Failed to create css file: '{:?}'", css_file_path));
 
                 file.write_all(compiled.as_bytes())
-                    .expect(format!("Failed to write file: {:?}", css_file_path).as_str());
+                    .unwrap_or_else(|_| panic!("Failed to write file: {:?}", css_file_path));
+
+                if context.dep_files {
+                    write_dep_file(&context, &css_file_path, &context.sass_dir.join(&source));
+                }
+
+                if let Some(hashed_name) = hashed_name {
+                    // The copy of the previous contents is replaced rather than left behind
+                    if let Some(previous) = previous.as_ref().filter(|previous| **previous != compiled) {
+                        let _ = fs::remove_file(css_dir.join(crate::hashed_name(&output_name, &crate::short_hash(previous))));
+                    }
+
+                    let hashed_path = css_dir.join(hashed_name);
+                    let written = create_output(&hashed_path, context.file_mode)
+                        .and_then(|mut file| file.write_all(compiled.as_bytes()));
+                    if let Err(e) = written {
+                        rocket::warn_!("Failed to write css file '{}': {}", hashed_path.display(), e);
+                    }
+                }
+            }
+
+            changed.sort();
+            stats.changed = changed;
+            stats.output_sizes.sort();
+        }
+
+        /// Same as `write_compiled_into`, for passes over every source. Records the
+        /// backend the outputs were written with, unless a source failed to compile
+        fn write_full_pass(&self, compiled_files: Vec<CompiledOutput>, stats: &mut CompileStats) {
+            self.write_compiled_into(compiled_files, stats);
+
+            let context = self.context();
+            if stats.failed == 0 && context.skip_fresh && !context.in_memory && context.writer.is_none() {
+                context.write_backend_id();
             }
         }
 
+        /// Compiles every source and writes the outputs into a zip archive instead of
+        /// `css_dir`, laid out like they would be in `css_dir`. Only covers this pair, as the
+        /// outputs of several would share one layout: archive each of `linked` on its own.
+        /// Fails with the first compile error, leaving the archive unfinished
+        #[cfg(feature = "archive")]
+        pub fn compile_all_to_zip<W>(&self, writer: W) -> Result<(), SassError>
+        where
+            W: Write + std::io::Seek,
+        {
+            let (compiled_files, stats) = self.compile_sources(Skip::Nothing);
+            if let Some(e) = stats.errors.into_iter().next() {
+                return Err(e);
+            }
+
+            let context = self.context();
+            let mut zip = zip::ZipWriter::new(writer);
+            let to_error = |e: &dyn std::fmt::Display| SassError::new(&context.css_dir, e.to_string());
+
+            let mut outputs: Vec<_> = compiled_files
+                .into_iter()
+                .map(|CompiledOutput { source, variant, css }| {
+                    ((context.output_name)(&variant), format_compiled(&context, &source, css))
+                })
+                .filter(|(_, compiled)| !(context.skip_empty && compiled.trim().is_empty()))
+                .collect();
+            outputs.sort();
+
+            for (output_name, compiled) in outputs {
+                // Zip entries always use forward slashes
+                let entry_name = output_name
+                    .components()
+                    .map(|component| component.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+
+                zip.start_file(entry_name, zip::write::FileOptions::default())
+                    .map_err(|e| to_error(&e))?;
+                zip.write_all(compiled.as_bytes()).map_err(|e| to_error(&e))?;
+            }
+
+            zip.finish().map_err(|e| to_error(&e))?;
+
+            Ok(())
+        }
+
+        /// Compiles every source, here and in the linked pairs, without writing and returns
+        /// the absolute paths of the outputs in `css_dir` that are missing or differ
+        pub fn verify(&self) -> (Vec<PathBuf>, CompileStats) {
+            let (compiled_files, mut stats) = self.compile_sources(Skip::Nothing);
+            let context = self.context();
+
+            let mut outdated: Vec<PathBuf> = compiled_files
+                .into_iter()
+                .filter(|output| !context.is_inline(&output.source))
+                .filter_map(|CompiledOutput { source, variant, css }| {
+                    let output_name = (context.output_name)(&variant);
+                    let compiled = format_compiled(&context, &source, css);
+
+                    let output = context.css_dir.join(output_name);
+                    match fs::read_to_string(&output).ok() {
+                        Some(existing) if existing == compiled => None,
+                        None if context.skip_empty && compiled.trim().is_empty() => None,
+                        _ => Some(output),
+                    }
+                })
+                .collect();
+
+            for linked in &self.linked {
+                let (linked_outdated, linked_stats) = linked.verify();
+                outdated.extend(linked_outdated);
+                stats.merge(linked_stats);
+            }
+            outdated.sort();
+
+            (outdated, stats)
+        }
+
         /// Shorthand for `compile_all` + `write_compiled`
-        pub fn compile_all_and_write(&self) {
-            if let Ok(compiled_files) = self.compile_all() {
-                self.write_compiled(compiled_files);
+        pub fn compile_all_and_write(&self) -> CompileStats {
+            let mut stats = self.compile_own_and_write();
+
+            for linked in &self.linked {
+                stats.merge(linked.compile_all_and_write());
             }
 
+            stats
+        }
+
+        /// Same as `compile_all_and_write`, leaving the linked pairs alone
+        fn compile_own_and_write(&self) -> CompileStats {
+            self.serialized(Pass::All)
+        }
+
+        /// Shorthand for `compile_stale` + `write_compiled`
+        pub fn compile_stale_and_write(&self) -> CompileStats {
+            let mut stats = self.serialized(Pass::Stale);
+
+            for linked in &self.linked {
+                stats.merge(linked.compile_stale_and_write());
+            }
+
+            stats
+        }
+
+        /// Recompiles and rewrites every source from scratch, for use after upgrading the
+        /// compiler or changing global options. Unlike `compile_all_and_write`, this
+        /// compiles sources whose css is in `cache_dir` (replacing it), and forgets the
+        /// errors, the in-memory css and the backend the outputs were written with before
+        pub fn recompile_all_force(&self) -> CompileStats {
+            let mut stats = self.serialized(Pass::Force);
+
+            for linked in &self.linked {
+                stats.merge(linked.recompile_all_force());
+            }
+
+            stats
+        }
+
+        /// Compiles and writes only the sources modified after `since`, along with the
+        /// ones loading a file modified after it, and returns the outputs that changed
+        /// (relative to `css_dir`). Waits for a compile already in progress instead of
+        /// deferring to it. Fails with the first compile error, once the sources that
+        /// compiled were written
+        pub fn compile_changed_since(&self, since: SystemTime) -> Result<Vec<PathBuf>, SassError> {
+            let stats = self.exclusive(|| {
+                let (compiled_files, mut stats) = self.compile_sources(Skip::UnchangedSince(since));
+                self.write_compiled_into(compiled_files, &mut stats);
+
+                stats
+            });
+
+            let mut changed = match stats.errors.into_iter().next() {
+                Some(e) => return Err(e),
+                None => stats.changed,
+            };
+
+            for linked in &self.linked {
+                changed.extend(linked.compile_changed_since(since)?);
+            }
+
+            Ok(changed)
+        }
+
+        /// Compiles and writes only the sources producing `output` (relative to `css_dir`),
+        /// if it is missing or older than them or any file they load. Returns `None` if
+        /// nothing was compiled, i.e. the output is up to date or no source produces it
+        pub fn compile_output_if_stale(&self, output: &Path) -> Option<CompileStats> {
+            let sources = self.own_sources_for(output);
+            if sources.is_empty() {
+                return self.linked.iter().find_map(|linked| linked.compile_output_if_stale(output));
+            }
+
+            let is_fresh = {
+                let context = self.context();
+                let output_mtime = fs::metadata(context.css_dir.join(output)).and_then(|meta| meta.modified());
+                let newest_source = sources.iter()
+                    .filter_map(|source| context.newest_dependency_mtime(&context.sass_dir.join(source)))
+                    .max();
+
+                output_mtime.is_ok_and(|mtime| newest_source.is_some_and(|newest| mtime >= newest))
+            };
+
+            if is_fresh {
+                return None;
+            }
+
+            Some(self.exclusive(|| {
+                let (compiled_files, mut stats) = self.compile_sources(Skip::Others(&sources));
+                self.write_compiled_into(compiled_files, &mut stats);
+
+                stats
+            }))
+        }
+
+        /// Runs the compile pass `pass` once any compile in progress finished, then
+        /// runs a full pass if one was requested in the meantime
+        fn exclusive(&self, pass: impl FnOnce() -> CompileStats) -> CompileStats {
+            let guard = self.compile_lock.lock().unwrap_or_else(|e| e.into_inner());
+
+            let stats = pass();
+            let _ = self.events.send(CompileEvent::from(&stats));
+
+            drop(guard);
+            let pending = *self.compile_pending.lock().unwrap();
+            if let Some(pass) = pending {
+                self.serialized(pass);
+            }
+
+            stats
+        }
+
+        /// Runs `pass` unless one is already running, in which case that one runs exactly
+        /// once more when it finishes and this returns deferred stats. That follow-up is the
+        /// strongest pass requested in the meantime, e.g. a forced one over a normal one.
+        /// Keeps `css_dir` consistent when the watcher, timer and routes trigger at once
+        fn serialized(&self, pass: Pass) -> CompileStats {
+            {
+                let mut pending = self.compile_pending.lock().unwrap();
+                *pending = (*pending).max(Some(pass));
+            }
+            let mut stats = CompileStats { deferred: true, ..Default::default() };
+
+            loop {
+                // A pass that panicked leaves nothing half done worth waiting on
+                let guard = match self.compile_lock.try_lock() {
+                    Ok(guard) => guard,
+                    Err(TryLockError::Poisoned(e)) => e.into_inner(),
+                    Err(TryLockError::WouldBlock) => return stats,
+                };
+
+                loop {
+                    let Some(pass) = self.compile_pending.lock().unwrap().take() else {
+                        break;
+                    };
+                    stats = self.run_pass(pass);
+
+                    // Failing only means nobody is listening
+                    let _ = self.events.send(CompileEvent::from(&stats));
+                }
+
+                // A request may have arrived after the last check but before unlocking
+                drop(guard);
+                if self.compile_pending.lock().unwrap().is_none() {
+                    return stats;
+                }
+            }
+        }
+
+        /// Compiles and writes every source of this pair as `pass` says, to be called
+        /// with `compile_lock` held
+        fn run_pass(&self, pass: Pass) -> CompileStats {
+            let skip = match pass {
+                Pass::Stale => Skip::Fresh,
+                Pass::All => Skip::Nothing,
+                Pass::Force => {
+                    self.last_errors.lock().unwrap().clear();
+                    self.memory_css.write().unwrap().clear();
+
+                    let context = self.context();
+                    if let Err(e) = fs::remove_file(context.backend_id_path()) {
+                        if e.kind() != std::io::ErrorKind::NotFound {
+                            rocket::warn_!("Failed to reset the sass backend of '{}': {}", context.css_dir.display(), e);
+                        }
+                    }
+
+                    Skip::Uncached
+                }
+            };
+
+            let (compiled_files, mut stats) = self.compile_sources(skip);
+            self.write_full_pass(compiled_files, &mut stats);
+
+            stats
+        }
+
+        /// Runs `compile_all_and_write` on a background thread whenever a message arrives
+        /// on `rx`, e.g. from a message queue consumer or a scheduled job. Messages arriving
+        /// during a pass are coalesced into one more pass. The thread exits once every
+        /// sender is dropped
+        pub fn recompile_on<T: Send + 'static>(&self, rx: std::sync::mpsc::Receiver<T>) {
+            let ctx_manager = self.clone();
+
+            std::thread::spawn(move || {
+                while rx.recv().is_ok() {
+                    rx.try_iter().for_each(drop);
+                    ctx_manager.compile_all_and_write();
+                }
+            });
+        }
+
+        /// Returns a sender triggering `compile_all_and_write` for each message sent,
+        /// see `recompile_on`
+        pub fn recompile_sender(&self) -> std::sync::mpsc::Sender<()> {
+            let (tx, rx) = std::sync::mpsc::channel();
+            self.recompile_on(rx);
+
+            tx
+        }
+
+        /// Returns a stream yielding an event each time a compile pass completes, whether
+        /// triggered by the watcher, a timer or a direct call, e.g. to push live reloads
+        /// over a websocket. A subscriber lagging far behind skips the oldest events
+        pub fn compile_events(&self) -> impl Stream<Item = CompileEvent> {
+            rocket::futures::stream::unfold(self.events.subscribe(), |mut rx| async move {
+                loop {
+                    match rx.recv().await {
+                        Ok(event) => return Some((event, rx)),
+                        Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(broadcast::error::RecvError::Closed) => return None,
+                    }
+                }
+            })
+        }
+
+        /// Returns the output (relative to `css_dir`) produced by `source`, given relative
+        /// to `sass_dir` or as a path inside it. Returns `None` if `source` produces no
+        /// output, e.g. a partial or a source left out of the entrypoints manifest.
+        /// With `sass_variants`, this is the output of the first variant, see `outputs_for`.
+        /// Falls back to the linked pairs, like `compiled_css`
+        pub fn output_for(&self, source: &Path) -> Option<PathBuf> {
+            self.outputs_for(source).into_iter().next()
+        }
+
+        /// Same as `output_for`, returning the output of every `sass_variants` entry,
+        /// e.g. `main.css` and `main.min.css` for `main.scss`
+        pub fn outputs_for(&self, source: &Path) -> Vec<PathBuf> {
+            let outputs = self.own_outputs_for(source);
+            if !outputs.is_empty() {
+                return outputs;
+            }
+
+            self.linked
+                .iter()
+                .map(|linked| linked.outputs_for(source))
+                .find(|outputs| !outputs.is_empty())
+                .unwrap_or_default()
+        }
+
+        /// Same as `outputs_for`, leaving the linked pairs alone
+        fn own_outputs_for(&self, source: &Path) -> Vec<PathBuf> {
+            let context = self.context();
+
+            outputs_of(&context, &context.entrypoints(), source)
+        }
+
+        /// Returns the absolute paths of the files the next writing pass would write into
+        /// `css_dir`, here and in the linked pairs, without compiling anything. Outputs kept
+        /// in memory are left out, as are the empty ones `sass_skip_empty` would skip
+        /// since that is only known once compiled. Includes the `.d` files of `sass_dep_files`
+        /// and, with `sass_cache_bust = "filename"`, the hashed copies of the outputs
+        /// already in `css_dir`, named after their current contents
+        pub fn planned_outputs(&self) -> Vec<PathBuf> {
+            let mut outputs = Vec::new();
+
+            {
+                let context = self.context();
+
+                if !context.is_embedded() && !context.in_memory {
+                    for source in source_files(&context) {
+                        if context.is_inline(&source) {
+                            continue;
+                        }
+
+                        let variants = match context.is_source_file(&source) {
+                            true => context.variants(&source).into_iter().map(|(variant_path, _)| variant_path).collect(),
+                            false => vec![source],
+                        };
+
+                        for variant_path in variants {
+                            let output = context.css_dir.join((context.output_name)(&variant_path));
+
+                            // A css file copied onto itself is not written
+                            if output == context.sass_dir.join(&variant_path) {
+                                continue;
+                            }
+
+                            if context.dep_files {
+                                let mut dep_file = output.as_os_str().to_owned();
+                                dep_file.push(".d");
+                                outputs.push(PathBuf::from(dep_file));
+                            }
+
+                            if context.cache_bust == CacheBust::Filename {
+                                if let Ok(css) = fs::read_to_string(&output) {
+                                    outputs.push(crate::hashed_name(&output, &crate::short_hash(&css)));
+                                }
+                            }
+
+                            outputs.push(output);
+                        }
+                    }
+                }
+            }
+
+            for linked in &self.linked {
+                outputs.extend(linked.planned_outputs());
+            }
+
+            outputs.sort();
+            outputs.dedup();
+
+            outputs
+        }
+
+        /// Returns the sources (relative to `sass_dir`) producing `output` (relative to
+        /// `css_dir`). Usually one, but the default flat naming maps same-named sources
+        /// of different folders to the same output. Falls back to the linked pairs when
+        /// no source of this one produces `output`, like `compiled_css`
+        pub fn sources_for(&self, output: &Path) -> Vec<PathBuf> {
+            let sources = self.own_sources_for(output);
+            if !sources.is_empty() {
+                return sources;
+            }
+
+            self.linked
+                .iter()
+                .map(|linked| linked.sources_for(output))
+                .find(|sources| !sources.is_empty())
+                .unwrap_or_default()
+        }
+
+        /// Same as `sources_for`, leaving the linked pairs alone
+        fn own_sources_for(&self, output: &Path) -> Vec<PathBuf> {
+            let context = self.context();
+            let candidates: Vec<PathBuf> = match context.is_embedded() {
+                true => context.embedded_sources.iter().map(|(name, _)| PathBuf::from(name)).collect(),
+                false => WalkDir::new(&context.sass_dir)
+                    .into_iter()
+                    .filter_map(|e| e.ok())
+                    .filter_map(|entry| entry.path().strip_prefix(&context.sass_dir).ok().map(Path::to_path_buf))
+                    .collect(),
+            };
+
+            // The manifest is read once rather than for every candidate
+            let entrypoints = context.entrypoints();
+            let mut sources: Vec<PathBuf> = candidates
+                .into_iter()
+                .filter(|source| outputs_of(&context, &entrypoints, source).iter().any(|source_output| source_output == output))
+                .collect();
+            sources.sort();
+
+            sources
+        }
+
+        /// Returns the error of the most recent compile of `output_name` (relative to
+        /// `css_dir`), or `None` if it last compiled successfully or was never compiled
+        pub fn last_error(&self, output_name: &str) -> Option<SassError> {
+            let last_error = self.last_errors.lock().unwrap().get(Path::new(output_name)).cloned();
+
+            last_error.or_else(|| self.linked.iter().find_map(|linked| linked.last_error(output_name)))
+        }
+
+        /// Returns the compiled css of the inline-only source producing `output_name`
+        /// (relative to `css_dir`), for inlining into templates
+        pub fn inline_css(&self, output_name: &str) -> Option<String> {
+            self.compiled_css(output_name)
+        }
+
+        /// Returns the in-memory css of `output_name` (relative to `css_dir`).
+        /// Holds every output in memory mode, otherwise only the inline-only ones
+        pub fn compiled_css(&self, output_name: &str) -> Option<String> {
+            let css = self.memory_css.read().unwrap().get(Path::new(output_name)).cloned();
+
+            css.or_else(|| self.linked.iter().find_map(|linked| linked.compiled_css(output_name)))
+        }
+
+        /// Returns a short hash of the current css of `output_name` (relative to `css_dir`),
+        /// in memory or in `css_dir`, e.g. to link it as `main.css?v=<hash>` so browsers
+        /// fetch it again once it changed. `None` if there is no such output
+        pub fn version_of(&self, output_name: &str) -> Option<String> {
+            let css = self.compiled_css(output_name).or_else(|| {
+                let context = self.context();
+                fs::read_to_string(context.css_dir.join(output_name)).ok()
+            });
+
+            css.map(|css| crate::short_hash(&css))
+                .or_else(|| self.linked.iter().find_map(|linked| linked.version_of(output_name)))
+        }
+
+        /// Returns the path (relative to `css_dir`) to link `output_name` with, following
+        /// `sass_cache_bust`: `main.css` as is, `main.css?v=<hash>` or `main.<hash>.css`.
+        /// `None` if there is no such output, see `version_of`
+        pub fn asset_url(&self, output_name: &str) -> Option<String> {
+            let version = self.version_of(output_name)?;
+
+            let url = match self.context().cache_bust {
+                CacheBust::None => output_name.to_string(),
+                CacheBust::Query => format!("{}?v={}", output_name, version),
+                CacheBust::Filename => crate::hashed_name(Path::new(output_name), &version).to_string_lossy().into_owned(),
+            };
+
+            Some(url)
         }
 
         /// Returns `true` if reloading
+        #[cfg(all(feature = "watch", debug_assertions))]
         pub fn is_reloading(&self) -> bool {
-            self.watcher.is_some()
+            self.watcher.is_some() && !self.watcher_lost.load(Ordering::SeqCst)
+        }
+
+        /// Returns `true` if reloading, which never happens in release builds
+        /// or without the `watch` feature
+        #[cfg(not(all(feature = "watch", debug_assertions)))]
+        pub fn is_reloading(&self) -> bool {
+            false
         }
 
-        /// Checks for any changes on `sass_dir`. 
+        /// Checks for any changes on `watch_dir`. 
         /// If found, compiles again (reloads)
+        #[cfg(all(feature = "watch", debug_assertions))]
         pub fn reload_if_needed(&self) {
-            let sass_changes = self.watcher.as_ref()
-                .map(|(_, rx)| rx.lock().expect("Failed to lock receiver").try_iter().count() > 0 );
+            for linked in &self.linked {
+                linked.reload_if_needed();
+            }
+
+            let (watched_ops, copy_css, extensions, manifest) = {
+                let context = self.context();
+                let ops = context.watch_events.iter()
+                    .fold(notify::Op::empty(), |ops, event| ops | notify::Op::from(*event));
+                let manifest = manifest_dir(&context).zip(context.entrypoints_manifest.file_name())
+                    .map(|(dir, name)| dir.join(name));
+
+                (ops, context.copy_css, context.extensions.clone(), manifest)
+            };
+
+            // Only compilable files and the entrypoints manifest matter, e.g. not a `.map` or an unrelated asset
+            let is_compilable = |path: &Option<PathBuf>| match path {
+                Some(path) => super::has_extension(path, &extensions) || (copy_css && is_css_file(path)) || Some(path) == manifest.as_ref(),
+                None => true,
+            };
+
+            let Some((_, rx)) = self.watcher.as_deref() else {
+                return;
+            };
+
+            // Unlike an empty channel, a disconnected one never yields events again
+            let mut events = Vec::new();
+            let disconnected = {
+                let rx = rx.lock().expect("Failed to lock receiver");
+                loop {
+                    match rx.try_recv() {
+                        Ok(event) => events.push(event),
+                        Err(mpsc::TryRecvError::Empty) => break false,
+                        Err(mpsc::TryRecvError::Disconnected) => break true,
+                    }
+                }
+            };
+
+            if disconnected && !self.watcher_lost.swap(true, Ordering::SeqCst) {
+                rocket::warn!("The sass watcher of '{}' stopped.", self.context().watch_dir.display());
+                rocket::warn_!("Live sass compiling is unavailable until restart.");
+            }
+
+            // Events the watcher failed to describe are treated as changes
+            let events: Vec<RawEvent> = events
+                .into_iter()
+                .filter(|event| event.op.as_ref().map_or(true, |op| op.intersects(watched_ops)))
+                .filter(|event| is_compilable(&event.path))
+                .collect();
+
+            if events.is_empty() {
+                return;
+            }
+
+            let mut sources: Vec<PathBuf> = {
+                let context = self.context();
+                events.into_iter()
+                    .filter_map(|event| event.path)
+                    .map(|path| path.strip_prefix(&context.watch_dir).map(Path::to_path_buf).unwrap_or(path))
+                    .collect()
+            };
+            sources.sort();
+            sources.dedup();
+
+            let stats = self.compile_own_and_write();
+            if stats.deferred {
+                rocket::info_!("Change detected: recompiling after the compile in progress.");
+                return;
+            }
+
+            let list = |paths: &[PathBuf]| paths.iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
 
-            if let Some(true) = sass_changes {
-                rocket::info_!("Change detected: compiling sass files.");
-                
-                self.compile_all_and_write();
+            match (sources.is_empty(), stats.changed.is_empty()) {
+                (true, true) => rocket::info_!("Change detected: recompiled sass files, no output changed."),
+                (true, false) => rocket::info_!("Change detected: recompiled {}.", list(&stats.changed)),
+                (false, true) => rocket::info_!("Changed {}: recompiled sass files, no output changed.", list(&sources)),
+                (false, false) => rocket::info_!("Changed {}: recompiled {}.", list(&sources), list(&stats.changed)),
             }
         }
     }
+
+    #[cfg(all(test, feature = "watch", debug_assertions))]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn a_disconnected_watcher_stops_reloading() {
+            let dir = std::env::temp_dir().join(format!("sass-rocket-fairing-watcher-lost-{}", std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(dir.join("sass")).unwrap();
+            fs::create_dir_all(dir.join("css")).unwrap();
+            let dir = dir.canonicalize().unwrap();
+            fs::write(dir.join("sass/main.scss"), "a { b: c; }\n").unwrap();
+
+            let ctx = Context::initialize(&dir.join("sass"), &dir.join("css"), Default::default(), Arc::new(crate::default_output_name)).unwrap();
+            let mut ctx_manager = ContextManager::new(ctx);
+            assert!(ctx_manager.is_reloading());
+
+            // Stands in for a watcher whose thread died after sending one last event
+            let (tx, rx) = mpsc::channel();
+            tx.send(RawEvent { path: Some(dir.join("sass/main.scss")), op: Ok(notify::Op::WRITE), cookie: None }).unwrap();
+            drop(tx);
+            let watcher = raw_watcher(mpsc::channel().0).unwrap();
+            ctx_manager.watcher = Some(Arc::new((watcher, Mutex::new(rx))));
+
+            ctx_manager.reload_if_needed();
+            assert!(!ctx_manager.is_reloading());
+            assert!(dir.join("css/main.css").exists(), "the events sent before disconnecting were dropped");
+
+            fs::remove_dir_all(dir).unwrap();
+        }
+    }
 }
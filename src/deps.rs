@@ -0,0 +1,123 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Rules whose urls load another sass file
+const LOADING_RULES: &[&str] = &["@use", "@forward", "@import"];
+
+/// Returns every sass file `source` depends on, directly or transitively, through
+/// `@use`, `@forward` and `@import`. Urls are resolved relative to the file loading
+/// them, then from `load_paths`. Built-in modules, plain css imports and urls
+/// that do not resolve to a file (e.g. ones handled by importers) are left out.
+///
+/// The returned paths are canonical
+pub fn dependencies(source: &Path, load_paths: &[PathBuf]) -> Vec<PathBuf> {
+    dependencies_with_extensions(source, load_paths, crate::SASS_EXTENSIONS)
+}
+
+/// Same as [`dependencies`], resolving urls to files with one of `extensions` rather
+/// than `.scss` and `.sass`, e.g. the `sass_extensions` of a `Context`
+pub fn dependencies_with_extensions<S: AsRef<str>>(source: &Path, load_paths: &[PathBuf], extensions: &[S]) -> Vec<PathBuf> {
+    let source = source.canonicalize().unwrap_or_else(|_| source.to_path_buf());
+    let mut found = BTreeSet::new();
+    let mut pending = vec![source.clone()];
+
+    while let Some(path) = pending.pop() {
+        let Ok(contents) = fs::read_to_string(&path) else {
+            continue;
+        };
+
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+        for url in loaded_urls(&contents) {
+            let resolved = std::iter::once(dir)
+                .chain(load_paths.iter().map(PathBuf::as_path))
+                .find_map(|base| resolve(base, &url, extensions));
+
+            if let Some(resolved) = resolved {
+                if resolved != source && found.insert(resolved.clone()) {
+                    pending.push(resolved);
+                }
+            }
+        }
+    }
+
+    found.into_iter().collect()
+}
+
+/// Returns the urls of the `@use`, `@forward` and `@import` rules in `contents`
+fn loaded_urls(contents: &str) -> Vec<String> {
+    let mut urls = Vec::new();
+
+    for line in contents.lines().map(str::trim_start) {
+        let Some(rest) = LOADING_RULES.iter().find_map(|rule| line.strip_prefix(rule)) else {
+            continue;
+        };
+
+        // `@imports` or `@user` are not loading rules
+        if !rest.starts_with(char::is_whitespace) {
+            continue;
+        }
+
+        // `@import 'a', 'b';` loads several urls, `@use 'a' as b;` only one
+        let mut rest = rest.trim_start();
+        while let Some(quote) = rest.chars().next().filter(|c| *c == '"' || *c == '\'') {
+            let body = &rest[1..];
+            let Some(end) = body.find(quote) else {
+                break;
+            };
+
+            let url = &body[..end];
+            if !is_plain_css(url) {
+                urls.push(url.to_string());
+            }
+
+            rest = body[end + 1..].trim_start();
+            match rest.strip_prefix(',') {
+                Some(next) => rest = next.trim_start(),
+                None => break,
+            }
+        }
+    }
+
+    urls
+}
+
+/// Returns `true` for urls the compiler leaves to the browser or provides itself
+fn is_plain_css(url: &str) -> bool {
+    url.ends_with(".css") || url.contains("://") || url.starts_with("sass:") || url.starts_with("url(")
+}
+
+/// Resolves `url` against `base` the way sass does: trying the partial and index
+/// forms of the file with each of `extensions`
+fn resolve<S: AsRef<str>>(base: &Path, url: &str, extensions: &[S]) -> Option<PathBuf> {
+    let path = base.join(url);
+    let file_name = path.file_name()?.to_str()?.to_string();
+    let dir = path.parent()?;
+
+    let has_extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| extensions.iter().any(|extension| extension.as_ref() == ext));
+    let candidates = if has_extension {
+        vec![dir.join(&file_name), dir.join(format!("_{}", file_name))]
+    } else {
+        extensions
+            .iter()
+            .map(AsRef::as_ref)
+            .flat_map(|ext| {
+                [
+                    dir.join(format!("{}.{}", file_name, ext)),
+                    dir.join(format!("_{}.{}", file_name, ext)),
+                    path.join(format!("index.{}", ext)),
+                    path.join(format!("_index.{}", ext)),
+                ]
+            })
+            .collect()
+    };
+
+    // Canonical paths keep `../` urls from visiting the same file under many names
+    candidates
+        .into_iter()
+        .find(|candidate| candidate.is_file())
+        .and_then(|candidate| candidate.canonicalize().ok())
+}
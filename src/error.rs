@@ -0,0 +1,98 @@
+use std::fmt;
+use std::path::PathBuf;
+
+/// An error raised while compiling a sass source
+#[derive(Debug, Clone)]
+pub struct SassError {
+    /// Path of the source that failed to compile
+    pub path: PathBuf,
+    /// Message reported by the compiler
+    pub message: String,
+}
+
+/// Where in the sources an error was raised
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLocation {
+    /// File containing the error as named by the compiler, e.g. a partial of the source
+    pub file: String,
+    pub line: u32,
+    pub column: u32,
+}
+
+impl SassError {
+    pub fn new(path: impl Into<PathBuf>, message: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Returns where the error was raised, read from the trace ending the compiler's
+    /// message (e.g. `_colors.scss 2:8  @use`). `None` if the message has no such trace
+    pub fn location(&self) -> Option<SourceLocation> {
+        self.message
+            .lines()
+            .skip_while(|line| line.trim() != "'")
+            .skip(1)
+            .find_map(|line| {
+                let what = line.trim().split("  ").next()?;
+                let (file, position) = what.rsplit_once(' ')?;
+                let (line, column) = position.split_once(':')?;
+
+                Some(SourceLocation {
+                    file: file.to_string(),
+                    line: line.parse().ok()?,
+                    column: column.parse().ok()?,
+                })
+            })
+    }
+
+    /// Renders this error as a css banner shown on top of the page.
+    /// Used in place of the stale output when `sass_error_overlay` is enabled
+    #[cfg(debug_assertions)]
+    pub(crate) fn overlay_css(&self) -> String {
+        let text = format!("Sass error in {}\n{}", self.path.display(), self.message);
+        let escaped = text
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\A ");
+
+        format!(
+            "body::before {{\n  content: \"{}\";\n  display: block;\n  position: fixed;\n  top: 0;\n  left: 0;\n  right: 0;\n  z-index: 2147483647;\n  padding: 1em;\n  white-space: pre-wrap;\n  font: 14px/1.4 monospace;\n  color: #fff;\n  background: #b00020;\n}}\n",
+            escaped
+        )
+    }
+}
+
+impl fmt::Display for SassError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.message)
+    }
+}
+
+impl std::error::Error for SassError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn location_is_the_innermost_file_of_the_trace() {
+        let e = SassError::new(
+            "main.scss",
+            "Parse error: Tag\n  ,\n2 | c { d: ; }\n  |        ^\n  '\n  _part.scss 2:8  @use\n  main.scss 1:1   root stylesheet",
+        );
+
+        let expected = SourceLocation {
+            file: "_part.scss".into(),
+            line: 2,
+            column: 8,
+        };
+        assert_eq!(e.location(), Some(expected));
+    }
+
+    #[test]
+    fn messages_without_a_trace_have_no_location() {
+        assert_eq!(SassError::new("main.scss", "The compiler panicked").location(), None);
+    }
+}
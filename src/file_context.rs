@@ -0,0 +1,116 @@
+use std::sync::Arc;
+
+use rsass::{Error, FsFileContext, SourceFile, SourceName, SourcePos};
+
+use crate::ImporterFn;
+
+/// Resolves imports from the filesystem like rsass' `FsFileContext`,
+/// but leaves `@import "*.css"` as a plain css `@import` rule instead
+/// of inlining the file, matching the behavior of dart-sass.
+///
+/// Importers are asked first, in order, before looking at the filesystem
+#[derive(Clone)]
+pub(crate) struct SassFileContext {
+    files: SourceFiles,
+    importers: Vec<Arc<ImporterFn>>,
+}
+
+impl SassFileContext {
+    pub(crate) fn new(files: FsFileContext, importers: &[Arc<ImporterFn>]) -> Self {
+        SassFileContext {
+            files: SourceFiles {
+                files,
+                extensions: Vec::new(),
+            },
+            importers: importers.to_vec(),
+        }
+    }
+
+    /// Also resolves urls to files with one of `extensions`, which are read as scss
+    pub(crate) fn with_extensions(mut self, extensions: &[String]) -> Self {
+        self.files.extensions = extensions.iter().filter(|ext| *ext != "scss" && *ext != "css").cloned().collect();
+        self
+    }
+
+    /// Returns the name and scss source produced by the first importer handling `url`
+    fn import(&self, url: &str) -> Option<(String, String)> {
+        let scss = self.importers.iter().find_map(|importer| importer(url))?;
+
+        // rsass picks the syntax from the file name
+        let name = match url.ends_with(".scss") {
+            true => url.to_string(),
+            false => format!("{}.scss", url),
+        };
+
+        Some((name, scss))
+    }
+}
+
+impl std::fmt::Debug for SassFileContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SassFileContext")
+            .field("files", &self.files)
+            .field("importers", &self.importers.len())
+            .finish()
+    }
+}
+
+impl rsass::FileContext for SassFileContext {
+    type File = std::fs::File;
+
+    fn find_file_import(&self, url: &str, from: SourcePos) -> Result<Option<SourceFile>, Error> {
+        if let Some((name, scss)) = self.import(url) {
+            return SourceFile::read(&mut scss.as_bytes(), SourceName::imported(name, from)).map(Some);
+        }
+
+        // Not finding a `.css` import makes rsass emit it as-is
+        if url.ends_with(".css") {
+            return Ok(None);
+        }
+
+        self.files.find_file_import(url, from)
+    }
+
+    fn find_file_use(&self, url: &str, from: SourcePos) -> Result<Option<SourceFile>, Error> {
+        if let Some((name, scss)) = self.import(url) {
+            return SourceFile::read(&mut scss.as_bytes(), SourceName::imported(name, from)).map(Some);
+        }
+
+        self.files.find_file_use(url, from)
+    }
+
+    fn find_file(&self, name: &str) -> Result<Option<(String, Self::File)>, Error> {
+        self.files.find_file(name)
+    }
+}
+
+/// The files of a `FsFileContext`, where every file rsass looks for as `.scss`
+/// is also looked for with the other source `extensions`
+#[derive(Clone, Debug)]
+struct SourceFiles {
+    files: FsFileContext,
+    extensions: Vec<String>,
+}
+
+impl rsass::FileContext for SourceFiles {
+    type File = std::fs::File;
+
+    fn find_file(&self, name: &str) -> Result<Option<(String, Self::File)>, Error> {
+        // rsass picks the syntax from the file name, so files with another extension get a `.scss` name
+        if let Some((found, file)) = self.files.find_file(name)? {
+            let is_extra = self.extensions.iter().any(|ext| found.ends_with(&format!(".{}", ext)));
+            return Ok(Some((if is_extra { format!("{}.scss", found) } else { found }, file)));
+        }
+
+        let Some(stem) = name.strip_suffix(".scss") else {
+            return Ok(None);
+        };
+        for ext in &self.extensions {
+            if let Some((_, file)) = self.files.find_file(&format!("{}.{}", stem, ext))? {
+                return Ok(Some((name.to_string(), file)));
+            }
+        }
+
+        Ok(None)
+    }
+}
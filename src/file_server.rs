@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+
+use rocket::http::{Method, Status};
+use rocket::route::{Handler, Outcome, Route};
+use rocket::{Data, Request};
+
+/// Rank of the route of a [`SassFileServer`] by default, the same as rocket's `FileServer`
+const DEFAULT_RANK: isize = 10;
+
+/// Serves the sass sources of a directory as css, analog to rocket's `FileServer`,
+/// without any fairing or `css_dir`. A request for `/foo.css` compiles `foo.scss`
+/// (or `foo.sass`); partials are never served.
+///
+/// Debug builds compile on request, whenever the source or a file it loads changed
+/// since the last compile. Release builds compile each stylesheet once and serve it
+/// from memory afterwards
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// use sass_rocket_fairing::SassFileServer;
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     rocket::build().mount("/css", SassFileServer::new("static/sass", Default::default()))
+/// }
+/// ```
+#[derive(Clone)]
+pub struct SassFileServer {
+    sass_dir: PathBuf,
+    format: rsass::output::Format,
+    rank: isize,
+    /// Compiled css and when it was compiled, keyed by source path
+    cache: Arc<RwLock<HashMap<PathBuf, (SystemTime, String)>>>,
+}
+
+impl SassFileServer {
+    /// Creates a server compiling the sources in `sass_dir` with `format`
+    pub fn new(sass_dir: impl AsRef<Path>, format: rsass::output::Format) -> Self {
+        Self {
+            sass_dir: sass_dir.as_ref().to_path_buf(),
+            format,
+            rank: DEFAULT_RANK,
+            cache: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Sets the rank of the route
+    pub fn rank(mut self, rank: isize) -> Self {
+        self.rank = rank;
+        self
+    }
+
+    /// Returns the source producing `output` (relative to `sass_dir`), if any
+    fn source_for(&self, output: &Path) -> Option<PathBuf> {
+        if output.extension().and_then(|ext| ext.to_str()) != Some("css") {
+            return None;
+        }
+
+        crate::SASS_EXTENSIONS
+            .iter()
+            .map(|ext| self.sass_dir.join(output.with_extension(ext)))
+            .find(|source| source.is_file() && !crate::is_partial(source))
+    }
+
+    /// Returns the css of `source`, compiling it unless the cached css is up to date
+    fn css(&self, source: &Path) -> Result<String, crate::SassError> {
+        let cached = self.cache.read().unwrap().get(source).cloned();
+
+        if let Some((compiled_at, css)) = cached {
+            // Release builds never recompile
+            if !cfg!(debug_assertions) {
+                return Ok(css);
+            }
+
+            let newest = std::iter::once(source.to_path_buf())
+                .chain(crate::dependencies(source, &[]))
+                .filter_map(|path| std::fs::metadata(path).ok()?.modified().ok())
+                .max();
+
+            if newest.is_some_and(|newest| newest <= compiled_at) {
+                return Ok(css);
+            }
+        }
+
+        let compiled_at = SystemTime::now();
+        let css = crate::compile_file(source.to_path_buf(), self.format)?;
+        self.cache.write().unwrap().insert(source.to_path_buf(), (compiled_at, css.clone()));
+
+        Ok(css)
+    }
+}
+
+#[rocket::async_trait]
+impl Handler for SassFileServer {
+    async fn handle<'r>(&self, req: &'r Request<'_>, data: Data<'r>) -> Outcome<'r> {
+        let Some(source) = req.segments::<PathBuf>(0..).ok().and_then(|path| self.source_for(&path)) else {
+            return Outcome::forward(data, Status::NotFound);
+        };
+
+        let css = match self.css(&source) {
+            Ok(css) => css,
+            Err(e) => {
+                rocket::error!("Failed to compile file '{}'", source.display());
+                rocket::error_!("Sass error: {}", e.message);
+                return Outcome::error(Status::InternalServerError);
+            }
+        };
+
+        // Css compiled on request must be revalidated, css compiled once may be kept
+        let cache_control = match cfg!(debug_assertions) {
+            true => "no-cache",
+            false => "public, max-age=3600",
+        };
+
+        Outcome::Success(crate::responder::css_response(req, css, cache_control))
+    }
+}
+
+impl From<SassFileServer> for Vec<Route> {
+    fn from(server: SassFileServer) -> Self {
+        vec![Route::ranked(server.rank, Method::Get, "/<path..>", server)]
+    }
+}
@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+use rsass::css::Value;
+use rsass::sass::{FormalArgs, Function, Name};
+use rsass::ScopeRef;
+
+/// Body of a host function, receiving the evaluated arguments in declaration order
+pub type SassFunctionFn = dyn Fn(&[Value]) -> Result<Value, String> + Send + Sync;
+
+/// A rust function callable from sass, see `SassFairing::function`
+#[derive(Clone)]
+pub struct SassFunction {
+    name: String,
+    params: Vec<String>,
+    body: Arc<SassFunctionFn>,
+}
+
+impl SassFunction {
+    /// Creates a function called `name` from sass, taking the arguments named `params`.
+    /// An error returned by `body` fails the compile with that message
+    pub fn new<F>(name: impl Into<String>, params: &[&str], body: F) -> Self
+    where
+        F: Fn(&[Value]) -> Result<Value, String> + Send + Sync + 'static,
+    {
+        SassFunction {
+            name: name.into(),
+            params: params.iter().map(|param| param.to_string()).collect(),
+            body: Arc::new(body),
+        }
+    }
+
+    /// Defines this function in `scope`
+    // The error type of builtin function bodies is set by rsass
+    #[allow(clippy::result_large_err)]
+    pub(crate) fn define(&self, scope: &ScopeRef) {
+        let name = Name::from(&self.name);
+        let params: Vec<Name> = self.params.iter().map(Name::from).collect();
+        let args = FormalArgs::new(params.iter().map(|param| (param.clone(), None)).collect());
+
+        let body = self.body.clone();
+        let function = Function::builtin("", &name, args, Arc::new(move |scope: &ScopeRef| {
+            let values = params.iter().map(|param| scope.get(param)).collect::<Result<Vec<_>, _>>()?;
+            body(&values).map_err(rsass::Error::BadValue)
+        }));
+
+        scope.define_function(name, function);
+    }
+}
@@ -1,41 +1,710 @@
+#[cfg(feature = "archive")]
+mod archive;
+pub mod build;
 mod context;
+mod deps;
+mod error;
+mod file_context;
+mod file_server;
+mod function;
+mod responder;
+mod rewrite;
+#[cfg(all(feature = "live_reload", debug_assertions))]
+mod routes;
 
 use rocket::{
     fairing::{Fairing, Info, Kind},
+    figment::Figment,
     log::PaintExt,
+    serde::{de::DeserializeOwned, Deserialize},
     yansi::Paint,
     Build, Orbit, Rocket,
 };
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use file_context::SassFileContext;
 
 // Re-exports
+#[cfg(feature = "archive")]
+pub use archive::compile_archive;
 // pub use sass_rs;
-pub use context::{Context, ContextManager};
+pub use context::{CompileEvent, CompileStats, Context, ContextManager, NamedContextManagers};
+pub use deps::{dependencies, dependencies_with_extensions};
+pub use error::{SassError, SourceLocation};
+pub use file_server::SassFileServer;
+pub use function::{SassFunction, SassFunctionFn};
+pub use responder::{mount_named_routes, mount_routes, CompiledCss};
+#[cfg(all(feature = "live_reload", debug_assertions))]
+pub use routes::{reload_now, ReloadError, ReloadReport};
 pub use rsass;
 
 const DEFAULT_SASS_DIR: &str = "static/sass";
 const DEFAULT_CSS_DIR: &str = "static/css";
 
+/// Output style baked in at build time, taking precedence over everything but the config.
+/// One of `expanded` or `compressed`
+const BUILD_STYLE: Option<&str> = option_env!("SASS_ROCKET_FAIRING_STYLE");
+
+/// Numeric precision baked in at build time, taking precedence over everything but the config
+const BUILD_PRECISION: Option<&str> = option_env!("SASS_ROCKET_FAIRING_PRECISION");
+
+/// Version of the rsass dependency, part of `Context::backend_id`.
+/// Resolved from the lockfile by the build script
+const RSASS_VERSION: &str = env!("SASS_ROCKET_FAIRING_RSASS_VERSION");
+
+/// Trivial scss compiled at ignite to check the compiler is usable
+const HEALTH_CHECK_SCSS: &str = "a{b:c}";
+
+/// File extensions recognized as compilable sass sources
+const SASS_EXTENSIONS: &[&str] = &["scss", "sass"];
+
+/// Returns `true` if the path has one of the sass source extensions
+fn is_sass_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| SASS_EXTENSIONS.contains(&ext))
+}
+
+/// Returns `true` if the path is a sass partial, i.e. its file name starts with `_`.
+/// Partials are only meant to be imported and never produce an output of their own
+pub fn is_partial(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with('_'))
+}
+
+/// Output style selectable through the `sass_style` config key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(crate = "rocket::serde", rename_all = "lowercase")]
+pub enum OutputStyle {
+    Expanded,
+    Compressed,
+}
+
+impl From<OutputStyle> for rsass::output::Style {
+    fn from(style: OutputStyle) -> Self {
+        match style {
+            OutputStyle::Expanded => rsass::output::Style::Expanded,
+            OutputStyle::Compressed => rsass::output::Style::Compressed,
+        }
+    }
+}
+
+/// Where compiled css goes, selectable through the `sass_mode` config key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(crate = "rocket::serde", rename_all = "lowercase")]
+pub enum OutputMode {
+    /// Writes to `css_dir`, falling back to memory if it is not writable
+    Auto,
+    /// Always writes to `css_dir`
+    Disk,
+    /// Keeps everything in memory, see `ContextManager::compiled_css`
+    Memory,
+}
+
+/// How sources map to outputs in `css_dir`, selectable through the `sass_output_layout` config key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(crate = "rocket::serde", rename_all = "kebab-case")]
+pub enum OutputLayout {
+    /// Names outputs with `SassFairing::output_name`, by default flat into `css_dir`
+    Flat,
+    /// Same as `Flat`, but a compile pass fails when two sources produce the same output
+    FlatStrict,
+}
+
+/// How links to outputs change along with their css, selectable through the
+/// `sass_cache_bust` config key, see `ContextManager::asset_url`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(crate = "rocket::serde", rename_all = "lowercase")]
+pub enum CacheBust {
+    /// Links outputs by their plain name
+    None,
+    /// Appends a `?v=<hash>` query string to the plain name
+    Query,
+    /// Also writes each output as `<name>.<hash>.css`, linked instead of the plain one
+    Filename,
+}
+
+/// Line endings of written css, selectable through the `sass_line_endings` config key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(crate = "rocket::serde", rename_all = "lowercase")]
+pub enum LineEndings {
+    Lf,
+    Crlf,
+}
+
+impl LineEndings {
+    /// Converts every line ending in `css` to this style
+    pub fn apply(self, css: &str) -> String {
+        let lf = css.replace("\r\n", "\n");
+
+        match self {
+            LineEndings::Lf => lf,
+            LineEndings::Crlf => lf.replace('\n', "\r\n"),
+        }
+    }
+}
+
+/// Indentation of expanded css, selectable through the `sass_indent_type` config key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(crate = "rocket::serde", rename_all = "lowercase")]
+pub enum IndentType {
+    Space,
+    Tab,
+}
+
+/// Indentation rsass uses for each nesting level of expanded output
+const RSASS_INDENT: usize = 2;
+
+/// Replaces the leading indentation of every line in `css` with `indent` per nesting level
+pub(crate) fn reindent(css: &str, indent: &str) -> String {
+    css.split_inclusive('\n')
+        .map(|line| {
+            let trimmed = line.trim_start_matches(' ');
+            let level = (line.len() - trimmed.len()) / RSASS_INDENT;
+            indent.repeat(level) + trimmed
+        })
+        .collect()
+}
+
+/// Kind of file system event, selectable through the `sass_watch_events` config key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(crate = "rocket::serde", rename_all = "snake_case")]
+pub enum WatchEvent {
+    Create,
+    Write,
+    CloseWrite,
+    Remove,
+    Rename,
+    Chmod,
+    Rescan,
+}
+
+impl WatchEvent {
+    /// Events that can change the compiled output, which excludes pure metadata changes
+    pub const DEFAULT: &'static [WatchEvent] = &[
+        WatchEvent::Create,
+        WatchEvent::Write,
+        WatchEvent::CloseWrite,
+        WatchEvent::Remove,
+        WatchEvent::Rename,
+        WatchEvent::Rescan,
+    ];
+}
+
+#[cfg(feature = "watch")]
+impl From<WatchEvent> for notify::Op {
+    fn from(event: WatchEvent) -> Self {
+        match event {
+            WatchEvent::Create => notify::Op::CREATE,
+            WatchEvent::Write => notify::Op::WRITE,
+            WatchEvent::CloseWrite => notify::Op::CLOSE_WRITE,
+            WatchEvent::Remove => notify::Op::REMOVE,
+            WatchEvent::Rename => notify::Op::RENAME,
+            WatchEvent::Chmod => notify::Op::CHMOD,
+            WatchEvent::Rescan => notify::Op::RESCAN,
+        }
+    }
+}
+
+/// Maps a source path (relative to `sass_dir`) to an output path (relative to `css_dir`)
+pub type OutputNameFn = dyn Fn(&Path) -> PathBuf + Send + Sync;
+
+/// Post-processes compiled css before it is written
+pub type TransformFn = dyn Fn(&str) -> Result<String, SassError> + Send + Sync;
+
+/// Persists an output, given its path relative to `css_dir` and its css,
+/// in place of writing it to `css_dir`
+pub type WriterFn = dyn Fn(&Path, &str) -> Result<(), SassError> + Send + Sync;
+
+/// Returns the scss source of an `@use`/`@import` url, or `None` to leave it to the next
+/// importer and finally the filesystem, see `SassFairing::importer`
+pub type ImporterFn = dyn Fn(&str) -> Option<String> + Send + Sync;
+
+/// Default output naming: the source's file name with a `.css` extension,
+/// written directly into `css_dir`
+pub fn default_output_name(source: &Path) -> PathBuf {
+    let mut output = PathBuf::from(source.file_name().unwrap_or_default());
+    output.set_extension("css");
+
+    output
+}
+
+/// FNV-1a hasher. Unlike the std one, its hashes stay the same across runs and rust
+/// versions, so they can name files kept between restarts
+pub(crate) struct StableHasher(u64);
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        StableHasher(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl std::hash::Hasher for StableHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Returns a short hash of `contents`, e.g. to version a url with
+pub(crate) fn short_hash(contents: &str) -> String {
+    use std::hash::Hasher;
+
+    let mut hasher = StableHasher::default();
+    hasher.write(contents.as_bytes());
+
+    format!("{:016x}", hasher.finish())[..8].to_string()
+}
+
+/// Inserts `hash` before the extension of `output_name`, e.g. `main.1a2b3c4d.css`
+pub(crate) fn hashed_name(output_name: &Path, hash: &str) -> PathBuf {
+    let stem = output_name.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = output_name.extension().unwrap_or_default().to_string_lossy();
+
+    output_name.with_file_name(format!("{}.{}.{}", stem, hash, extension))
+}
+
+/// Places the scss `prelude` before `source`, e.g. to define variables from config.
+/// Plain css sources are left alone
+fn with_prelude(source: rsass::Parsed, prelude: &str) -> Result<rsass::Parsed, rsass::ParseError> {
+    match source {
+        rsass::Parsed::Scss(items) if !prelude.is_empty() => {
+            let mut prelude = rsass::parse_scss_data(prelude.as_bytes())?;
+            prelude.extend(items);
+
+            Ok(rsass::Parsed::Scss(prelude))
+        }
+        source => Ok(source),
+    }
+}
+
+/// Value of a variable set under the `sass_vars` config table
+#[derive(Debug, Clone, Deserialize)]
+#[serde(crate = "rocket::serde", untagged)]
+enum SassVar {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+}
+
+/// Returns scss defining each of `vars`, e.g. `$environment: "production";`
+fn vars_prelude(vars: &std::collections::BTreeMap<String, SassVar>) -> String {
+    vars.iter()
+        .map(|(name, value)| {
+            let value = match value {
+                SassVar::Bool(value) => value.to_string(),
+                SassVar::Int(value) => value.to_string(),
+                SassVar::Float(value) => value.to_string(),
+                SassVar::String(value) => format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\"")),
+            };
+
+            format!("${}: {};\n", name, value)
+        })
+        .collect()
+}
+
+/// Creates the global scope of a compile, defining `functions` in it
+fn global_scope(format: rsass::output::Format, functions: &[SassFunction]) -> rsass::ScopeRef {
+    let scope = rsass::ScopeRef::new_global(format);
+    for function in functions {
+        function.define(&scope);
+    }
+
+    scope
+}
+
 /// Compiles a single sass file and returns the resultant `String`
+/// Using the rsass format specified.
+///
+/// `@import`s of urls ending in `.css` are passed through as plain css `@import` rules
+pub fn compile_file(path_buf: PathBuf, format: rsass::output::Format) -> Result<String, SassError> {
+    compile_file_with_load_paths(path_buf, format, &[])
+}
+
+/// Same as `compile_file`, but also resolves `@use`/`@import` from `load_paths`
+/// when they are not found relative to the compiled file
+pub fn compile_file_with_load_paths(
+    path_buf: PathBuf,
+    format: rsass::output::Format,
+    load_paths: &[PathBuf],
+) -> Result<String, SassError> {
+    compile_file_with(path_buf, format, load_paths, &[], &[], "", &[])
+}
+
+/// Same as `compile_file_with_load_paths`, with `functions` callable from sass,
+/// `importers` resolving urls before the filesystem, the scss `prelude` placed
+/// before the source and imports also resolved with the source `extensions`
+pub(crate) fn compile_file_with(
+    path_buf: PathBuf,
+    format: rsass::output::Format,
+    load_paths: &[PathBuf],
+    functions: &[SassFunction],
+    importers: &[Arc<ImporterFn>],
+    prelude: &str,
+    extensions: &[String],
+) -> Result<String, SassError> {
+    let to_error = |e: rsass::Error| SassError::new(&path_buf, e.to_string());
+
+    let (mut file_context, source) = read_source(&path_buf).map_err(to_error)?;
+    for load_path in load_paths {
+        file_context.push_path(load_path);
+    }
+
+    let source = with_prelude(source.parse().map_err(to_error)?, prelude)
+        .map_err(|e| SassError::new(&path_buf, e.to_string()))?;
+    let file_context = SassFileContext::new(file_context, importers).with_extensions(extensions);
+    let res = format
+        .write_root(source, global_scope(format, functions), &file_context)
+        .map_err(to_error)?;
+
+    Ok(String::from_utf8(res).unwrap())
+}
+
+/// Reads the source at `path` along with a file context resolving imports next to it.
+///
+/// rsass picks the syntax from the file name, so sources with another extension
+/// listed in `sass_extensions` are read as scss
+#[allow(clippy::result_large_err)]
+fn read_source(path: &Path) -> Result<(rsass::FsFileContext, rsass::SourceFile), rsass::Error> {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("scss") | Some("css") => rsass::FsFileContext::for_path(path),
+        _ => {
+            let mut file = std::fs::File::open(path).map_err(|e| rsass::Error::Input(path.display().to_string(), e))?;
+            let mut file_context = rsass::FsFileContext::new();
+            let base = path.parent().unwrap_or_else(|| Path::new(""));
+            file_context.push_path(base);
+
+            let name = format!("{}.scss", path.strip_prefix(base).unwrap_or(path).display());
+            let source = rsass::SourceFile::read(&mut file, rsass::SourceName::root(name))?;
+            Ok((file_context, source))
+        }
+    }
+}
+
+/// Same as `compile_file`, but also returns the source map json when one is produced.
+///
+/// rsass does not generate source maps, so the map is currently always `None`
+pub fn compile_file_with_map(
+    path_buf: PathBuf,
+    format: rsass::output::Format,
+) -> Result<(String, Option<String>), SassError> {
+    compile_file(path_buf, format).map(|css| (css, None))
+}
+
+/// Compiles sass source code and returns the resultant `String`
 /// Using the rsass format specified
-pub fn compile_file(path_buf: PathBuf, format: rsass::output::Format) -> Result<String, String> {
-    match rsass::compile_scss_path(path_buf.as_path(), format) {
-        Ok(res) => Ok(String::from_utf8(res).unwrap()),
-        Err(e) => Err(e.to_string()),
+pub fn compile_string(scss: &str, format: rsass::output::Format) -> Result<String, SassError> {
+    compile_string_with_load_paths("<string>", scss, format, &[])
+}
+
+/// Same as `compile_string`, but also resolves `@use`/`@import` from `load_paths`.
+/// `name` identifies the source in errors
+pub fn compile_string_with_load_paths(
+    name: &str,
+    scss: &str,
+    format: rsass::output::Format,
+    load_paths: &[PathBuf],
+) -> Result<String, SassError> {
+    compile_string_with(name, scss, format, load_paths, &[], &[], "")
+}
+
+/// Same as `compile_string_with_load_paths`, with `functions` callable from sass,
+/// `importers` resolving urls before the filesystem and the scss `prelude` placed
+/// before the source
+pub(crate) fn compile_string_with(
+    name: &str,
+    scss: &str,
+    format: rsass::output::Format,
+    load_paths: &[PathBuf],
+    functions: &[SassFunction],
+    importers: &[Arc<ImporterFn>],
+    prelude: &str,
+) -> Result<String, SassError> {
+    let to_error = |e: rsass::Error| SassError::new(name, e.to_string());
+
+    let mut file_context = rsass::FsFileContext::new();
+    for load_path in load_paths {
+        file_context.push_path(load_path);
+    }
+
+    let items = rsass::parse_scss_data(scss.as_bytes()).map_err(|e| SassError::new(name, e.to_string()))?;
+    let source = with_prelude(rsass::Parsed::Scss(items), prelude).map_err(|e| SassError::new(name, e.to_string()))?;
+    let res = format
+        .write_root(source, global_scope(format, functions), &SassFileContext::new(file_context, importers))
+        .map_err(to_error)?;
+
+    Ok(String::from_utf8(res).unwrap())
+}
+
+/// Returns the path of `key` under the `[sass]` table if set there, `key` itself otherwise.
+/// `sass_dir` becomes `sass.dir`, `css_dir` becomes `sass.css_dir` and other
+/// `sass_` keys lose their prefix, e.g. `sass_style` becomes `sass.style`
+fn config_key(figment: &Figment, key: &str) -> String {
+    let name = match key {
+        "sass_dir" => "dir",
+        key => key.strip_prefix("sass_").unwrap_or(key),
+    };
+
+    let namespaced = format!("sass.{}", name);
+    match figment.contains(&namespaced) {
+        true => namespaced,
+        false => key.to_string(),
+    }
+}
+
+/// Extracts the config value under `key`, using `default` when it is missing.
+/// Returns `None` (after printing the error) if the value is invalid
+fn extract_config<T>(figment: &Figment, key: &str, default: T) -> Option<T>
+where
+    T: DeserializeOwned,
+{
+    match figment.extract_inner::<T>(&config_key(figment, key)) {
+        Ok(value) => Some(value),
+        Err(e) if e.missing() => Some(default),
+        Err(e) => {
+            rocket::config::pretty_print_error(e);
+            None
+        }
+    }
+}
+
+/// Warns about settings that have no effect in combination with the others,
+/// turning off the ones that would misbehave
+fn check_config(figment: &Figment, ctx: &mut Context) {
+    let is_set = |key: &str| figment.contains(&config_key(figment, key));
+
+    if is_set("sass_dirs") && ctx.is_embedded() {
+        rocket::warn!("Embedded sources replace sass_dir, ignoring sass_dirs.");
+    } else if is_set("sass_dirs") && (is_set("sass_dir") || is_set("css_dir")) {
+        rocket::warn!("Directories are read from sass_dirs, ignoring sass_dir and css_dir.");
+    }
+
+    if ctx.in_memory && is_set("sass_mode") && ctx.is_embedded() {
+        rocket::warn!("Embedded sources are always kept in memory, ignoring sass_mode.");
+    }
+
+    // Outputs kept in memory are never written, files in css_dir would look up to date forever
+    if ctx.in_memory && ctx.skip_fresh {
+        rocket::warn!("Compiled css is kept in memory, ignoring sass_skip_fresh.");
+        ctx.skip_fresh = false;
+    }
+
+    // Only outputs written to css_dir can be checked for being up to date on request
+    if ctx.in_memory && ctx.lazy {
+        rocket::warn!("Compiled css is kept in memory, ignoring sass_lazy.");
+        ctx.lazy = false;
+    }
+
+    // Their output can't be hashed, a cache hit could hold css they would compile differently
+    if ctx.cache_dir.is_some() && (!ctx.functions.is_empty() || !ctx.importers.is_empty()) {
+        rocket::warn!("Custom functions and importers are set, ignoring sass_cache_dir.");
+        ctx.cache_dir = None;
+    }
+
+    if ctx.indent.is_some() && ctx.rsass_format.is_compressed() {
+        rocket::warn!("Compressed css is not indented, ignoring sass_indent_type and sass_indent_width.");
+    }
+
+    if !cfg!(unix) && is_set("sass_output_mode") {
+        rocket::warn!("File permissions are only set on Unix, ignoring sass_output_mode.");
+    }
+
+    if !cfg!(feature = "watch") {
+        for key in ["sass_watch_events", "sass_watch_dir"] {
+            if is_set(key) {
+                rocket::warn!("The watch feature is disabled, ignoring {}.", key);
+            }
+        }
+    }
+
+    if !cfg!(debug_assertions) {
+        for key in ["sass_watch_events", "sass_watch_dir", "sass_recompile_interval", "sass_error_overlay", "sass_lazy"] {
+            if is_set(key) {
+                rocket::warn!("Css is not recompiled in release builds, ignoring {}.", key);
+            }
+        }
     }
 }
 
+/// Recompiles everything every `interval` in a background task,
+/// independently of the file system watcher
+#[cfg(debug_assertions)]
+fn spawn_interval_recompile(ctx_manager: ContextManager, interval: std::time::Duration) {
+    rocket::tokio::spawn(async move {
+        let mut ticker = rocket::tokio::time::interval(interval);
+        // The first tick completes immediately, right after the liftoff precompile
+        ticker.tick().await;
+
+        loop {
+            ticker.tick().await;
+
+            let ctx_manager = ctx_manager.clone();
+            let _ = rocket::tokio::task::spawn_blocking(move || ctx_manager.compile_all_and_write()).await;
+        }
+    });
+}
+
+/// A `(sass_dir, css_dir)` pair of the `sass_dirs` config key
+#[derive(Debug, Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct DirPair {
+    #[serde(rename = "in")]
+    input: rocket::figment::value::magic::RelativePathBuf,
+    #[serde(rename = "out")]
+    output: rocket::figment::value::magic::RelativePathBuf,
+}
+
 /// Main user facing rocket `Fairing`
 pub struct SassFairing {
-    rsass_format: rsass::output::Format,
+    name: Option<String>,
+    rsass_format: Option<rsass::output::Format>,
+    output_name: Arc<OutputNameFn>,
+    transforms: Vec<Arc<TransformFn>>,
+    writer: Option<Arc<WriterFn>>,
+    embedded_sources: Vec<(String, String)>,
+    functions: Vec<SassFunction>,
+    importers: Vec<Arc<ImporterFn>>,
+    load_paths: Vec<PathBuf>,
 }
 
 impl SassFairing {
-    /// Creates a new `SassFairing` with the specified format
+    /// Creates a new `SassFairing` with the specified format.
+    /// The `sass_style` config key still takes precedence over the format's style
     pub fn new(format: rsass::output::Format) -> Self {
         Self {
-            rsass_format: format,
+            rsass_format: Some(format),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the function used to map each source file to its output path.
+    /// Defaults to [`default_output_name`]
+    pub fn output_name<F>(mut self, output_name: F) -> Self
+    where
+        F: Fn(&Path) -> PathBuf + Send + Sync + 'static,
+    {
+        self.output_name = Arc::new(output_name);
+        self
+    }
+
+    /// Adds a transform run on each compiled output before it is written.
+    /// Transforms run in the order they were added; an error fails that file's compile
+    pub fn transform<F>(mut self, transform: F) -> Self
+    where
+        F: Fn(&str) -> Result<String, SassError> + Send + Sync + 'static,
+    {
+        self.transforms.push(Arc::new(transform));
+        self
+    }
+
+    /// Sets the function persisting each output instead of writing it to `css_dir`,
+    /// e.g. to upload it to object storage. It is given the output path relative to
+    /// `css_dir` and the css; an error is logged and counted as a failed output.
+    /// Outputs kept in memory are not passed to it
+    ///
+    /// ```rust
+    /// use sass_rocket_fairing::SassFairing;
+    ///
+    /// let fairing = SassFairing::default().writer(|output, css| {
+    ///     println!("would upload {} ({} bytes)", output.display(), css.len());
+    ///     Ok(())
+    /// });
+    /// ```
+    pub fn writer<F>(mut self, writer: F) -> Self
+    where
+        F: Fn(&Path, &str) -> Result<(), SassError> + Send + Sync + 'static,
+    {
+        self.writer = Some(Arc::new(writer));
+        self
+    }
+
+    /// Adds a rust function callable from every compiled source, e.g. to expose app settings
+    ///
+    /// ```rust
+    /// use sass_rocket_fairing::{SassFairing, SassFunction};
+    /// use sass_rocket_fairing::rsass::css::Value;
+    ///
+    /// let fairing = SassFairing::default().function(SassFunction::new("brand-color", &[], |_| {
+    ///     Ok(Value::Literal("rebeccapurple".into()))
+    /// }));
+    /// ```
+    pub fn function(mut self, function: SassFunction) -> Self {
+        self.functions.push(function);
+        self
+    }
+
+    /// Adds an importer resolving `@use`/`@import` urls from app logic, e.g. to generate
+    /// a `theme:dark` stylesheet. Importers are asked in the order they were added,
+    /// before looking for files
+    pub fn importer<F>(mut self, importer: F) -> Self
+    where
+        F: Fn(&str) -> Option<String> + Send + Sync + 'static,
+    {
+        self.importers.push(Arc::new(importer));
+        self
+    }
+
+    /// Adds a directory `@use`/`@import` urls are also resolved from, tried after the
+    /// ones of the `sass_load_paths` and `sass_workspace_paths` config keys.
+    /// A relative path is resolved against the current directory
+    pub fn load_path(mut self, path: impl AsRef<Path>) -> Self {
+        self.load_paths.push(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Adds a source compiled from memory, e.g. one embedded with `include_str!`.
+    /// Once any is added, `sass_dir` is not used at all: the embedded sources are
+    /// compiled at ignite and their css is kept in memory, see `ContextManager::compiled_css`.
+    /// `name` plays the role of the path relative to `sass_dir` (e.g. `"main.scss"`)
+    pub fn embedded_source(mut self, name: impl Into<String>, scss: impl Into<String>) -> Self {
+        self.embedded_sources.push((name.into(), scss.into()));
+        self
+    }
+
+    /// Turns this into a named instance, allowing multiple `SassFairing`s to be attached.
+    /// A named instance reads its config keys from the table of the same name
+    /// (e.g. `[default.admin]`) and registers its `ContextManager` in `NamedContextManagers`
+    pub fn named(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Returns the figment this instance reads its config from
+    fn figment(&self, rocket: &Rocket<Build>) -> Figment {
+        match &self.name {
+            Some(name) => rocket.figment().focus(name),
+            None => rocket.figment().clone(),
+        }
+    }
+
+    /// Calls `f` with the `ContextManager` registered by this instance.
+    /// Does nothing if none was registered, i.e. an optional `sass_dir` was missing
+    fn with_context_manager<P: rocket::Phase>(&self, rocket: &Rocket<P>, f: impl FnOnce(&ContextManager)) {
+        match &self.name {
+            Some(name) => {
+                let ctx_manager = rocket
+                    .state::<NamedContextManagers>()
+                    .and_then(|managers| managers.get(name));
+
+                if let Some(ctx_manager) = ctx_manager {
+                    f(&ctx_manager)
+                }
+            }
+            None => {
+                if let Some(ctx_manager) = rocket.state::<ContextManager>() {
+                    f(ctx_manager)
+                }
+            }
         }
     }
 }
@@ -43,7 +712,15 @@ impl SassFairing {
 impl Default for SassFairing {
     fn default() -> Self {
         Self {
-            rsass_format: rsass::output::Format::default(),
+            name: None,
+            rsass_format: None,
+            output_name: Arc::new(default_output_name),
+            transforms: Vec::new(),
+            writer: None,
+            embedded_sources: Vec::new(),
+            functions: Vec::new(),
+            importers: Vec::new(),
+            load_paths: Vec::new(),
         }
     }
 }
@@ -51,10 +728,13 @@ impl Default for SassFairing {
 #[rocket::async_trait]
 impl Fairing for SassFairing {
     fn info(&self) -> Info {
-        let kind = Kind::Ignite | Kind::Liftoff | Kind::Singleton;
+        let kind = Kind::Ignite | Kind::Liftoff;
 
-        // Enable Request Kind in debug mode
-        #[cfg(debug_assertions)]
+        // Only named instances may be attached more than once
+        let kind = if self.name.is_none() { kind | Kind::Singleton } else { kind };
+
+        // Enable Request Kind in debug mode, when there is a watcher to check
+        #[cfg(all(feature = "watch", debug_assertions))]
         let kind = kind | Kind::Request;
 
         Info {
@@ -66,10 +746,11 @@ impl Fairing for SassFairing {
     async fn on_ignite(&self, rocket: Rocket<Build>) -> rocket::fairing::Result {
         use rocket::figment::value::magic::RelativePathBuf;
 
+        let figment = self.figment(&rocket);
+
         // Get sass directory
-        let sass_dir = rocket
-            .figment()
-            .extract_inner::<RelativePathBuf>("sass_dir")
+        let sass_dir = figment
+            .extract_inner::<RelativePathBuf>(&config_key(&figment, "sass_dir"))
             .map(|path| path.relative());
 
         let sass_path = match sass_dir {
@@ -82,9 +763,8 @@ impl Fairing for SassFairing {
         };
 
         // Get css directory
-        let css_dir = rocket
-            .figment()
-            .extract_inner::<RelativePathBuf>("css_dir")
+        let css_dir = figment
+            .extract_inner::<RelativePathBuf>(&config_key(&figment, "css_dir"))
             .map(|path| path.relative());
 
         let css_path = match css_dir {
@@ -96,50 +776,535 @@ impl Fairing for SassFairing {
             }
         };
 
-        if let Some(ctx) = Context::initialize(&sass_path, &css_path, self.rsass_format) {
-            Ok(rocket.manage(ContextManager::new(ctx)))
+        // Get (sass_dir, css_dir) pairs, the first one taking the place of sass_dir and css_dir
+        let Some(dirs) = extract_config::<Vec<DirPair>>(&figment, "sass_dirs", vec![]) else {
+            return Err(rocket);
+        };
+        let mut dirs = dirs.into_iter().map(|pair| (pair.input.relative(), pair.output.relative()));
+        let (sass_path, css_path) = dirs.next().unwrap_or((sass_path, css_path));
+
+        // Get output style, defaulting to the one baked in at build time,
+        // then to compressed output under the release profile
+        let default_style = match (BUILD_STYLE, self.rsass_format) {
+            (Some("expanded"), _) => OutputStyle::Expanded,
+            (Some("compressed"), _) => OutputStyle::Compressed,
+            (Some(style), _) => {
+                rocket::error!("Invalid SASS_ROCKET_FAIRING_STYLE '{}' set at build time.", style);
+                return Err(rocket);
+            }
+            (None, Some(format)) if format.is_compressed() => OutputStyle::Compressed,
+            (None, Some(_)) => OutputStyle::Expanded,
+            (None, None) if figment.profile() == rocket::Config::RELEASE_PROFILE => OutputStyle::Compressed,
+            (None, None) => OutputStyle::Expanded,
+        };
+
+        let Some(style) = extract_config(&figment, "sass_style", default_style) else {
+            return Err(rocket);
+        };
+
+        // Get the number of decimals of numbers, defaulting to the one baked in at build time,
+        // then to the format's (rsass uses 10)
+        let default_precision = match BUILD_PRECISION.map(str::parse) {
+            Some(Ok(precision)) => precision,
+            Some(Err(e)) => {
+                rocket::error!("Invalid SASS_ROCKET_FAIRING_PRECISION set at build time: {}.", e);
+                return Err(rocket);
+            }
+            None => self.rsass_format.unwrap_or_default().precision,
+        };
+        let Some(precision) = extract_config(&figment, "sass_precision", default_precision) else {
+            return Err(rocket);
+        };
+
+        let rsass_format = rsass::output::Format {
+            style: style.into(),
+            precision,
+        };
+
+        // Make sure the compiler works at all before touching any real file
+        if let Err(e) = compile_string(HEALTH_CHECK_SCSS, rsass_format) {
+            rocket::error!("The sass compiler failed to compile '{}': {}", HEALTH_CHECK_SCSS, e.message);
+            rocket::error_!("Sass Initialization failed. Aborting launch.");
+            return Err(rocket);
+        }
+
+        // Get whether a missing sass_dir disables the fairing instead of aborting launch
+        let Some(optional) = extract_config(&figment, "sass_optional", false) else {
+            return Err(rocket);
+        };
+        if optional && self.embedded_sources.is_empty() && !sass_path.is_dir() {
+            rocket::warn!("Sass directory '{}' does not exist.", sass_path.display());
+            rocket::warn_!("Sass compiling is disabled.");
+            return Ok(rocket);
+        }
+
+        let ctx = if self.embedded_sources.is_empty() {
+            Context::initialize(&sass_path, &css_path, rsass_format, self.output_name.clone())
         } else {
+            Some(Context::embedded(self.embedded_sources.clone(), rsass_format, self.output_name.clone()))
+        };
+
+        let Some(mut ctx) = ctx else {
             rocket::error!("Sass Initialization failed. Aborting launch.");
-            Err(rocket)
+            return Err(rocket);
+        };
+
+        // Get the directory watched for changes, sass_dir by default
+        let watch_dir = figment
+            .extract_inner::<RelativePathBuf>(&config_key(&figment, "sass_watch_dir"))
+            .map(|path| path.relative());
+
+        match watch_dir {
+            Ok(watch_dir) => {
+                if ctx.set_watch_dir(&watch_dir).is_none() {
+                    rocket::error!("Sass Initialization failed. Aborting launch.");
+                    return Err(rocket);
+                }
+            }
+            Err(e) if e.missing() => {}
+            Err(e) => {
+                rocket::config::pretty_print_error(e);
+                return Err(rocket);
+            }
+        }
+
+        // Get the entrypoints manifest, looked up in sass_dir by default
+        let manifest = figment
+            .extract_inner::<RelativePathBuf>(&config_key(&figment, "sass_entrypoints"))
+            .map(|path| path.relative());
+
+        match manifest {
+            Ok(manifest) => ctx.entrypoints_manifest = manifest,
+            Err(e) if e.missing() => {}
+            Err(e) => {
+                rocket::config::pretty_print_error(e);
+                return Err(rocket);
+            }
+        }
+
+        // Get error overlay toggle, only used in debug builds
+        let Some(error_overlay) = extract_config(&figment, "sass_error_overlay", false) else {
+            return Err(rocket);
+        };
+        ctx.error_overlay = error_overlay;
+
+        // Get whether the liftoff precompile skips up to date outputs
+        let Some(skip_fresh) = extract_config(&figment, "sass_skip_fresh", false) else {
+            return Err(rocket);
+        };
+        ctx.skip_fresh = skip_fresh;
+
+        // Get whether failed compile passes end with a summary of their errors
+        let Some(error_summary) = extract_config(&figment, "sass_error_summary", false) else {
+            return Err(rocket);
+        };
+        ctx.error_summary = error_summary;
+
+        // Get whether changed outputs log a summary of their diff
+        let Some(log_diff) = extract_config(&figment, "sass_log_diff", false) else {
+            return Err(rocket);
+        };
+        ctx.log_diff = log_diff;
+
+        // Get whether the liftoff banner is printed
+        let Some(banner_log) = extract_config(&figment, "sass_banner_log", true) else {
+            return Err(rocket);
+        };
+        ctx.banner_log = banner_log;
+
+        // Get how links to outputs change along with their css
+        let Some(cache_bust) = extract_config(&figment, "sass_cache_bust", CacheBust::None) else {
+            return Err(rocket);
+        };
+        ctx.cache_bust = cache_bust;
+
+        // Get the time in seconds browsers may keep css served from memory
+        let Some(cache_max_age) = extract_config::<Option<u64>>(&figment, "sass_cache_max_age", None) else {
+            return Err(rocket);
+        };
+        ctx.cache_max_age = cache_max_age.map(std::time::Duration::from_secs);
+
+        // Get the file extensions of the sources in sass_dir
+        let Some(extensions) = extract_config::<Option<Vec<String>>>(&figment, "sass_extensions", None) else {
+            return Err(rocket);
+        };
+        if let Some(extensions) = extensions {
+            ctx.extensions = extensions.into_iter().map(|ext| ext.trim_start_matches('.').to_string()).collect();
+        }
+
+        // Get the styles every source is compiled in, each to its own output
+        let Some(variants) = extract_config::<Vec<OutputStyle>>(&figment, "sass_variants", vec![]) else {
+            return Err(rocket);
+        };
+        ctx.variants = variants;
+
+        // Get whether compile passes log failures, size budgets and diffs
+        let Some(compile_log) = extract_config(&figment, "sass_compile_log", true) else {
+            return Err(rocket);
+        };
+        ctx.compile_log = compile_log;
+
+        // Get whether a Makefile style dependency file is written next to each output
+        let Some(dep_files) = extract_config(&figment, "sass_dep_files", false) else {
+            return Err(rocket);
+        };
+        ctx.dep_files = dep_files;
+
+        // Get whether outputs compile when requested instead of on every change
+        let Some(lazy) = extract_config(&figment, "sass_lazy", false) else {
+            return Err(rocket);
+        };
+        ctx.lazy = lazy;
+
+        // Get whether empty outputs are written
+        let Some(skip_empty) = extract_config(&figment, "sass_skip_empty", false) else {
+            return Err(rocket);
+        };
+        ctx.skip_empty = skip_empty;
+
+        // Get the line endings of written css, leaving them as compiled by default
+        let Some(line_endings) = extract_config::<Option<LineEndings>>(&figment, "sass_line_endings", None) else {
+            return Err(rocket);
+        };
+        ctx.line_endings = line_endings;
+
+        // Get whether plain css files in sass_dir are copied over as is
+        let Some(copy_css) = extract_config(&figment, "sass_copy_css", false) else {
+            return Err(rocket);
+        };
+        ctx.copy_css = copy_css;
+
+        // Get the indentation of expanded css, leaving it as compiled by default
+        let Some(indent_type) = extract_config::<Option<IndentType>>(&figment, "sass_indent_type", None) else {
+            return Err(rocket);
+        };
+        let Some(indent_width) = extract_config::<Option<usize>>(&figment, "sass_indent_width", None) else {
+            return Err(rocket);
+        };
+        ctx.indent = match (indent_type, indent_width) {
+            (None, None) => None,
+            (Some(IndentType::Tab), width) => Some("\t".repeat(width.unwrap_or(1))),
+            (_, width) => Some(" ".repeat(width.unwrap_or(RSASS_INDENT))),
+        };
+
+        // Get the file system events triggering a reload, only used in debug builds
+        let Some(watch_events) = extract_config(&figment, "sass_watch_events", WatchEvent::DEFAULT.to_vec()) else {
+            return Err(rocket);
+        };
+        ctx.watch_events = watch_events;
+
+        // Get where compiled css goes
+        let Some(output_mode) = extract_config(&figment, "sass_mode", OutputMode::Auto) else {
+            return Err(rocket);
+        };
+        ctx.in_memory = ctx.is_embedded() || match output_mode {
+            OutputMode::Auto if !ctx.is_css_dir_writable() => {
+                rocket::warn!("Css directory '{}' is not writable.", ctx.css_dir.display());
+                rocket::warn_!("Keeping compiled css in memory instead.");
+                true
+            }
+            OutputMode::Auto | OutputMode::Disk => false,
+            OutputMode::Memory => true,
+        };
+
+        // Get whether same-named outputs fail compile passes
+        let Some(output_layout) = extract_config(&figment, "sass_output_layout", OutputLayout::Flat) else {
+            return Err(rocket);
+        };
+        ctx.output_layout = output_layout;
+
+        // Get the interval of timed recompiles in seconds, only used in debug builds
+        let Some(recompile_interval) = extract_config::<Option<u64>>(&figment, "sass_recompile_interval", None) else {
+            return Err(rocket);
+        };
+        ctx.recompile_interval = recompile_interval.filter(|secs| *secs > 0).map(std::time::Duration::from_secs);
+
+        // Get the time in seconds after which compiling a single file fails
+        let Some(compile_timeout) = extract_config::<Option<u64>>(&figment, "sass_compile_timeout", None) else {
+            return Err(rocket);
+        };
+        ctx.compile_timeout = compile_timeout.filter(|secs| *secs > 0).map(std::time::Duration::from_secs);
+
+        // Get the sources kept in memory for inlining
+        let Some(inline_sources) = extract_config::<Vec<String>>(&figment, "sass_inline", vec![]) else {
+            return Err(rocket);
+        };
+        for source in inline_sources {
+            match glob::Pattern::new(&source) {
+                Ok(pattern) => ctx.inline_patterns.push(pattern),
+                Err(e) => {
+                    rocket::error!("Invalid sass_inline pattern '{}': {}.", source, e);
+                    return Err(rocket);
+                }
+            }
+        }
+
+        // Get base urls of fonts and images, applied before sass_url_base so they take precedence
+        let Some(font_base) = extract_config::<Option<String>>(&figment, "sass_font_base", None) else {
+            return Err(rocket);
+        };
+        let Some(image_base) = extract_config::<Option<String>>(&figment, "sass_image_base", None) else {
+            return Err(rocket);
+        };
+        if font_base.is_some() || image_base.is_some() {
+            ctx.transforms.push(Arc::new(move |css: &str| {
+                Ok(rewrite::prefix_asset_urls(css, font_base.as_deref(), image_base.as_deref()))
+            }));
+        }
+
+        // Get base url prefixed to relative `url()`s, applied before any user transform
+        let Some(url_base) = extract_config::<Option<String>>(&figment, "sass_url_base", None) else {
+            return Err(rocket);
+        };
+        if let Some(url_base) = url_base {
+            ctx.transforms.push(Arc::new(move |css: &str| Ok(rewrite::prefix_urls(css, &url_base))));
+        }
+        ctx.transforms.extend(self.transforms.iter().cloned());
+        ctx.writer = self.writer.clone();
+        ctx.functions = self.functions.clone();
+        ctx.importers = self.importers.clone();
+
+        // Get sass variables defined before every source
+        let Some(vars) = extract_config(&figment, "sass_vars", std::collections::BTreeMap::new()) else {
+            return Err(rocket);
+        };
+        ctx.prelude = vars_prelude(&vars);
+
+        // Get load paths, relative to the config file
+        let Some(config_load_paths) = extract_config::<Vec<RelativePathBuf>>(&figment, "sass_load_paths", vec![]) else {
+            return Err(rocket);
+        };
+        let config_load_paths: Vec<PathBuf> = config_load_paths.iter().map(RelativePathBuf::relative).collect();
+        if ctx.add_load_paths(&config_load_paths).is_none() {
+            rocket::error!("Sass Initialization failed. Aborting launch.");
+            return Err(rocket);
+        }
+
+        // Get load paths relative to the cargo workspace root
+        let Some(workspace_paths) = extract_config::<Vec<PathBuf>>(&figment, "sass_workspace_paths", vec![]) else {
+            return Err(rocket);
+        };
+        if ctx.add_workspace_paths(&workspace_paths).is_none() {
+            rocket::error!("Sass Initialization failed. Aborting launch.");
+            return Err(rocket);
+        }
+
+        // Load paths added with `SassFairing::load_path` come after the configured ones
+        if ctx.add_load_paths(&self.load_paths).is_none() {
+            rocket::error!("Sass Initialization failed. Aborting launch.");
+            return Err(rocket);
+        }
+
+        // Get the permissions of written outputs, only used on Unix
+        let Some(file_mode) = extract_config::<Option<u32>>(&figment, "sass_output_mode", None) else {
+            return Err(rocket);
+        };
+        ctx.file_mode = file_mode;
+
+        // Get the directory caching compiled css across restarts, relative to the config file
+        let Some(cache_dir) = extract_config::<Option<RelativePathBuf>>(&figment, "sass_cache_dir", None) else {
+            return Err(rocket);
+        };
+        ctx.cache_dir = cache_dir.map(|dir| dir.relative());
+
+        // Get the size budget of each output in bytes
+        let Some(max_css_bytes) = extract_config::<Option<usize>>(&figment, "sass_max_css_bytes", None) else {
+            return Err(rocket);
+        };
+        ctx.max_css_bytes = max_css_bytes;
+
+        // Get whether a failing source aborts launch instead of serving stale css
+        let Some(abort_on_error) = extract_config(&figment, "sass_abort_on_error", false) else {
+            return Err(rocket);
+        };
+
+        // Get whether the outputs in css_dir are compared against their sources at ignite
+        let Some(verify) = extract_config(&figment, "sass_verify", false) else {
+            return Err(rocket);
+        };
+
+        check_config(&figment, &mut ctx);
+
+        let mut linked = Vec::new();
+        if !ctx.is_embedded() {
+            for (sass_path, css_path) in dirs {
+                let Some(pair_ctx) = ctx.for_dirs(&sass_path, &css_path) else {
+                    rocket::error!("Sass Initialization failed. Aborting launch.");
+                    return Err(rocket);
+                };
+
+                linked.push(ContextManager::new(pair_ctx));
+            }
+        }
+
+        let ctx_manager = linked.into_iter().fold(ContextManager::new(ctx), ContextManager::link);
+
+        if verify {
+            let (outdated, stats) = ctx_manager.verify();
+            let current_dir = std::env::current_dir().unwrap_or_default();
+            for output in &outdated {
+                let output = output.strip_prefix(&current_dir).unwrap_or(output);
+                rocket::warn!("Css file '{}' is out of date.", output.display());
+            }
+
+            if abort_on_error && (stats.failed > 0 || !outdated.is_empty()) {
+                rocket::error!("Sass verification failed. Aborting launch.");
+                return Err(rocket);
+            }
+        }
+
+        let in_memory = ctx_manager.context().in_memory;
+
+        // Liftoff runs with the server already up, so check the sources here,
+        // unless verifying just compiled every one of them
+        if abort_on_error && !verify && !in_memory {
+            let pairs = std::iter::once(&ctx_manager).chain(ctx_manager.linked());
+            let failed: usize = pairs.map(|pair| pair.compile_all().1.failed).sum();
+            if failed > 0 {
+                rocket::error!("Sass compilation failed. Aborting launch.");
+                return Err(rocket);
+            }
+        }
+
+        // Outputs kept in memory have no previous css on disk to fall back to, so they
+        // are compiled upfront and broken sources abort launch in release builds
+        if in_memory {
+            let stats = ctx_manager.compile_all_and_write();
+            if stats.failed > 0 && (abort_on_error || !cfg!(debug_assertions)) {
+                rocket::error!("Sass compilation failed. Aborting launch.");
+                return Err(rocket);
+            }
+        }
+
+        match &self.name {
+            Some(name) => {
+                let rocket = match rocket.state::<NamedContextManagers>() {
+                    Some(_) => rocket,
+                    None => rocket.manage(NamedContextManagers::default()),
+                };
+
+                rocket.state::<NamedContextManagers>().unwrap().insert(name, ctx_manager);
+                Ok(rocket)
+            }
+            None => Ok(rocket.manage(ctx_manager)),
         }
     }
 
     async fn on_liftoff(&self, rocket: &Rocket<Orbit>) {
-        let ctx_manager = rocket
-            .state::<ContextManager>()
-            .expect("Sass Context not registered in on_ignite");
+        self.with_context_manager(rocket, |ctx_manager| {
+            let context = &*ctx_manager.context();
 
-        let context = &*ctx_manager.context();
+            // Show the directories relative to the current directory when possible
+            let current_dir = std::env::current_dir().unwrap();
+            let sass_dir = context.sass_dir.strip_prefix(&current_dir).unwrap_or(&context.sass_dir);
+            let css_dir = context.css_dir.strip_prefix(&current_dir).unwrap_or(&context.css_dir);
 
-        let sass_dir = context
-            .sass_dir
-            .strip_prefix(std::env::current_dir().unwrap())
-            .expect("sass_dir is not defined");
-        let css_dir = context
-            .css_dir
-            .strip_prefix(std::env::current_dir().unwrap())
-            .expect("css_dir is not defined");
+            // Only the decorative banner is silenced, errors and warnings are still logged
+            if context.banner_log {
+                match &self.name {
+                    Some(name) => rocket::info!("{}{} ({}):", "✨ ".emoji(), "Sass".magenta(), name),
+                    None => rocket::info!("{}{}:", "✨ ".emoji(), "Sass".magenta()),
+                }
 
-        rocket::info!("{}{}:", Paint::emoji("✨ "), Paint::magenta("Sass"));
-        rocket::info_!("sass directory: {}", Paint::white(sass_dir.display()));
-        rocket::info_!("css directory: {}", Paint::white(css_dir.display()));
+                if context.is_embedded() {
+                    rocket::info_!("embedded sources: {}", context.embedded_sources.len().white());
+                } else {
+                    rocket::info_!("sass directory: {}", sass_dir.display().white());
+                    rocket::info_!("css directory: {}", css_dir.display().white());
 
-        // Precompile sass files if in debug mode
-        if cfg!(debug_assertions) {
-            rocket::info_!("pre-compiling sass files");
-            ctx_manager.compile_all_and_write();
-        }
+                    for linked in ctx_manager.linked() {
+                        let linked = &*linked.context();
+                        let sass_dir = linked.sass_dir.strip_prefix(&current_dir).unwrap_or(&linked.sass_dir);
+                        let css_dir = linked.css_dir.strip_prefix(&current_dir).unwrap_or(&linked.css_dir);
+                        rocket::info_!("also: {} -> {}", sass_dir.display().white(), css_dir.display().white());
+                    }
+
+                    #[cfg(debug_assertions)]
+                    if context.watch_dir != context.sass_dir {
+                        let watch_dir = context.watch_dir.strip_prefix(&current_dir).unwrap_or(&context.watch_dir);
+                        rocket::info_!("watch directory: {}", watch_dir.display().white());
+                    }
+
+                    if context.in_memory {
+                        rocket::info_!("keeping css in memory");
+                    }
+                }
+            }
+
+            if context.is_embedded() {
+                return;
+            }
+
+            // Precompile sass files if in debug mode, outputs kept in memory were compiled at ignite
+            if cfg!(debug_assertions) && !context.in_memory {
+                if context.banner_log {
+                    rocket::info_!("pre-compiling sass files");
+                }
+
+                let stats = if context.skip_fresh {
+                    ctx_manager.compile_stale_and_write()
+                } else {
+                    ctx_manager.compile_all_and_write()
+                };
+
+                if context.banner_log {
+                    rocket::info_!("compiled {} files ({} failed)", stats.compiled, stats.failed);
+                    rocket::info_!("css size: {} bytes", stats.total_bytes());
+                    if stats.skipped > 0 {
+                        rocket::info_!("skipped {} up to date files", stats.skipped);
+                    }
+                }
+            }
+
+            #[cfg(debug_assertions)]
+            if let Some(interval) = context.recompile_interval {
+                if context.banner_log {
+                    rocket::info_!("recompiling every {}s", interval.as_secs());
+                }
+                spawn_interval_recompile(ctx_manager.clone(), interval);
+            }
+        });
     }
 
     /// Calls `ContextManager.reload_if_needed` on new incoming request.
-    /// Only applicable in debug builds
-    #[cfg(debug_assertions)]
+    /// Only applicable in debug builds with the `watch` feature
+    #[cfg(all(feature = "watch", debug_assertions))]
     async fn on_request(&self, req: &mut rocket::Request<'_>, _data: &mut rocket::Data<'_>) {
-        let context_manager = req
-            .rocket()
-            .state::<ContextManager>()
-            .expect("Sass ContextManager not registered in on_ignite");
+        self.with_context_manager(req.rocket(), |ctx_manager| ctx_manager.reload_if_needed());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_endings_convert_mixed_endings() {
+        let css = "a {\r\n  b: c;\n}\n";
+
+        assert_eq!(LineEndings::Lf.apply(css), "a {\n  b: c;\n}\n");
+        assert_eq!(LineEndings::Crlf.apply(css), "a {\r\n  b: c;\r\n}\r\n");
+    }
+
+    #[test]
+    fn reindent_replaces_each_level_of_rsass_indentation() {
+        let css = "a {\n  b: c;\n}\n@media print {\n  a {\n    b: c;\n  }\n}\n";
+
+        assert_eq!(reindent(css, "\t"), "a {\n\tb: c;\n}\n@media print {\n\ta {\n\t\tb: c;\n\t}\n}\n");
+        assert_eq!(reindent(css, "    "), "a {\n    b: c;\n}\n@media print {\n    a {\n        b: c;\n    }\n}\n");
+    }
+
+    #[test]
+    fn vars_prelude_defines_each_variable() {
+        let vars = vec![
+            ("debug", SassVar::Bool(true)),
+            ("columns", SassVar::Int(12)),
+            ("ratio", SassVar::Float(1.5)),
+            ("env", SassVar::String("say \"hi\" \\o/".into())),
+        ];
+        let vars = vars.into_iter().map(|(name, value)| (name.to_string(), value)).collect();
 
-        context_manager.reload_if_needed();
+        let expected = "$columns: 12;\n$debug: true;\n$env: \"say \\\"hi\\\" \\\\o/\";\n$ratio: 1.5;\n";
+        assert_eq!(vars_prelude(&vars), expected);
     }
 }
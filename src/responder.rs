@@ -0,0 +1,174 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use rocket::http::{ContentType, Header, Method, Status};
+use rocket::response::{self, Responder, Response};
+use rocket::route::{Handler, Outcome, Route};
+use rocket::{Build, Data, Request, Rocket};
+
+use crate::{ContextManager, NamedContextManagers};
+
+/// Responds with the in-memory css of an output, looked up in the managed `ContextManager`.
+///
+/// Responds with `404 Not Found` if the output is not held in memory,
+/// see `ContextManager::compiled_css`. The css is tagged for caching, see `css_response`
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// use sass_rocket_fairing::CompiledCss;
+///
+/// #[get("/css/<name>")]
+/// fn css(name: &str) -> CompiledCss {
+///     CompiledCss::new(name)
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CompiledCss {
+    output_name: String,
+}
+
+impl CompiledCss {
+    /// Creates a responder for `output_name`, relative to `css_dir`
+    pub fn new(output_name: impl Into<String>) -> Self {
+        CompiledCss {
+            output_name: output_name.into(),
+        }
+    }
+}
+
+impl<'r> Responder<'r, 'static> for CompiledCss {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        let ctx_manager = request.rocket().state::<ContextManager>().ok_or(Status::NotFound)?;
+        let css = ctx_manager.compiled_css(&self.output_name).ok_or(Status::NotFound)?;
+        let cache_control = cache_control(ctx_manager.context().cache_max_age);
+
+        Ok(css_response(request, css, &cache_control))
+    }
+}
+
+/// Returns the `Cache-Control` value letting browsers keep css for `max_age`, or
+/// making them revalidate it on every use without one, see `sass_cache_max_age`
+pub(crate) fn cache_control(max_age: Option<Duration>) -> String {
+    match max_age {
+        Some(max_age) => format!("public, max-age={}", max_age.as_secs()),
+        None => "no-cache".to_string(),
+    }
+}
+
+/// Responds with `css` as `text/css; charset=utf-8`, tagged with an `ETag` of its
+/// content hash, or with `304 Not Modified` if the `If-None-Match` of `req` holds
+/// that tag already. Either way with the given `Cache-Control`
+pub(crate) fn css_response(req: &Request<'_>, css: String, cache_control: &str) -> Response<'static> {
+    let etag = format!("\"{}\"", crate::short_hash(&css));
+    let is_cached = req
+        .headers()
+        .get("If-None-Match")
+        .flat_map(|tags| tags.split(','))
+        .map(str::trim)
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag);
+
+    let mut response = Response::build();
+    response
+        .header(Header::new("ETag", etag))
+        .header(Header::new("Cache-Control", cache_control.to_string()));
+
+    match is_cached {
+        true => response.status(Status::NotModified),
+        false => response.header(ContentType::CSS).sized_body(css.len(), std::io::Cursor::new(css)),
+    };
+
+    response.finalize()
+}
+
+/// Serves every output held in memory by the `ContextManager` of an instance, by its
+/// path relative to `css_dir`
+#[derive(Clone)]
+struct CompiledCssHandler {
+    /// Name of the instance, `None` for the unnamed one
+    instance: Option<String>,
+}
+
+impl CompiledCssHandler {
+    /// Looks up the manager when a request comes in, as the fairing only registers it
+    /// once ignited, after the routes were mounted
+    fn ctx_manager(&self, req: &Request<'_>) -> Option<ContextManager> {
+        match &self.instance {
+            Some(name) => req.rocket().state::<NamedContextManagers>()?.get(name).map(|ctx_manager| (*ctx_manager).clone()),
+            None => req.rocket().state::<ContextManager>().cloned(),
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl Handler for CompiledCssHandler {
+    async fn handle<'r>(&self, req: &'r Request<'_>, data: Data<'r>) -> Outcome<'r> {
+        let Ok(path) = req.segments::<PathBuf>(0..) else {
+            return Outcome::forward(data, Status::NotFound);
+        };
+
+        // E.g. an optional sass_dir that is missing
+        let Some(ctx_manager) = self.ctx_manager(req) else {
+            return Outcome::forward(data, Status::NotFound);
+        };
+
+        // Lazy outputs are brought up to date here, on disk, before forwarding
+        let lazy = cfg!(debug_assertions) && ctx_manager.context().lazy;
+        if lazy {
+            let (ctx_manager, output) = (ctx_manager.clone(), path.clone());
+            let _ = rocket::tokio::task::spawn_blocking(move || ctx_manager.compile_output_if_stale(&output)).await;
+        }
+
+        let css = ctx_manager.compiled_css(&path.to_string_lossy());
+
+        match css {
+            Some(css) => {
+                let cache_control = cache_control(ctx_manager.context().cache_max_age);
+                Outcome::Success(css_response(req, css, &cache_control))
+            }
+            None => Outcome::forward(data, Status::NotFound),
+        }
+    }
+}
+
+/// Rank of the route mounted by [`mount_routes`], the same as rocket's `FileServer`
+const ROUTE_RANK: isize = 10;
+
+/// Mounts a route at `prefix` serving the in-memory css of the `SassFairing`, e.g.
+/// `/css/main.css` for `main.css` with a `/css` prefix. Outputs not held in memory are
+/// forwarded to the next route, so a `FileServer` can serve them from `css_dir`.
+/// Responses are tagged for caching like those of [`CompiledCss`].
+/// With `sass_lazy = true`, debug builds compile each requested output here first,
+/// if it is out of date, see `ContextManager::compile_output_if_stale`.
+///
+/// The `ContextManager` is looked up on each request, so this can be called before
+/// the fairing is ignited. See [`mount_named_routes`] for named instances
+///
+/// ```rust
+/// use sass_rocket_fairing::SassFairing;
+///
+/// let rocket = rocket::build().attach(SassFairing::default());
+/// let rocket = sass_rocket_fairing::mount_routes(rocket, "/css");
+///
+/// assert!(rocket.routes().any(|route| route.uri.path() == "/css/<path..>"));
+/// ```
+pub fn mount_routes(rocket: Rocket<Build>, prefix: &str) -> Rocket<Build> {
+    mount_handler(rocket, CompiledCssHandler { instance: None }, prefix)
+}
+
+/// Same as [`mount_routes`], for the instance named `name` with `SassFairing::named`
+///
+/// ```rust
+/// use sass_rocket_fairing::SassFairing;
+///
+/// let rocket = rocket::build().attach(SassFairing::default().named("admin"));
+/// let rocket = sass_rocket_fairing::mount_named_routes(rocket, "admin", "/admin/css");
+///
+/// assert!(rocket.routes().any(|route| route.uri.path() == "/admin/css/<path..>"));
+/// ```
+pub fn mount_named_routes(rocket: Rocket<Build>, name: &str, prefix: &str) -> Rocket<Build> {
+    mount_handler(rocket, CompiledCssHandler { instance: Some(name.to_string()) }, prefix)
+}
+
+fn mount_handler(rocket: Rocket<Build>, handler: CompiledCssHandler, prefix: &str) -> Rocket<Build> {
+    rocket.mount(prefix, vec![Route::ranked(ROUTE_RANK, Method::Get, "/<path..>", handler)])
+}
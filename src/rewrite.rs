@@ -0,0 +1,148 @@
+/// Extensions of `url()` targets rewritten with `sass_font_base`
+const FONT_EXTENSIONS: &[&str] = &["woff", "woff2", "ttf", "otf", "eot"];
+
+/// Extensions of `url()` targets rewritten with `sass_image_base`
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "svg", "webp", "avif", "ico", "bmp"];
+
+/// Returns `true` for `url()` targets that already resolve on their own
+fn is_absolute_url(url: &str) -> bool {
+    url.is_empty()
+        || url.starts_with('/')
+        || url.starts_with('#')
+        || url.starts_with("data:")
+        || url.contains("://")
+}
+
+/// Calls `rewrite` on the target of every `url()` in `css`, keeping its quoting
+pub(crate) fn map_urls(css: &str, rewrite: impl Fn(&str) -> Option<String>) -> String {
+    let mut output = String::with_capacity(css.len());
+    let mut rest = css;
+
+    while let Some(start) = rest.find("url(") {
+        let (before, after) = rest.split_at(start + "url(".len());
+        output.push_str(before);
+
+        let trimmed = after.trim_start();
+        output.push_str(&after[..after.len() - trimmed.len()]);
+
+        let quote = trimmed.chars().next().filter(|c| *c == '"' || *c == '\'');
+        let body = &trimmed[quote.map_or(0, |q| q.len_utf8())..];
+        let end = match quote {
+            Some(q) => body.find(q),
+            None => body.find(')'),
+        };
+
+        let Some(end) = end else {
+            // Unterminated `url(`, leave the remainder untouched
+            rest = trimmed;
+            break;
+        };
+
+        let url = &body[..end];
+        let url = rewrite(url).unwrap_or_else(|| url.to_string());
+
+        if let Some(q) = quote {
+            output.push(q);
+            output.push_str(&url);
+            output.push(q);
+            rest = &body[end + q.len_utf8()..];
+        } else {
+            output.push_str(&url);
+            rest = &body[end..];
+        }
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// Joins the relative `url` onto `base`
+fn join_url(base: &str, url: &str) -> String {
+    format!("{}/{}", base.trim_end_matches('/'), url.trim_start_matches("./"))
+}
+
+/// Prefixes every relative `url()` target in `css` with `base`
+pub(crate) fn prefix_urls(css: &str, base: &str) -> String {
+    map_urls(css, |url| {
+        if is_absolute_url(url) {
+            return None;
+        }
+
+        Some(join_url(base, url))
+    })
+}
+
+/// Prefixes relative `url()` targets in `css` with `font_base` or `image_base`
+/// depending on their extension, leaving other targets untouched
+pub(crate) fn prefix_asset_urls(css: &str, font_base: Option<&str>, image_base: Option<&str>) -> String {
+    map_urls(css, |url| {
+        if is_absolute_url(url) {
+            return None;
+        }
+
+        // `font.woff2?v=3` and `icons.svg#home` are still fonts and images
+        let path = url.split(['?', '#']).next().unwrap_or(url);
+        let extension = path.rsplit_once('.')?.1.to_ascii_lowercase();
+
+        let base = if FONT_EXTENSIONS.contains(&extension.as_str()) {
+            font_base
+        } else if IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+            image_base
+        } else {
+            None
+        };
+
+        Some(join_url(base?, url))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_urls_keeps_quotes_and_whitespace() {
+        let css = "a { b: url( 'x.png' ); c: url(\"y.png\"); d: url(z.png); }";
+        let mapped = map_urls(css, |url| Some(format!("/{}", url)));
+
+        assert_eq!(mapped, "a { b: url( '/x.png' ); c: url(\"/y.png\"); d: url(/z.png); }");
+    }
+
+    #[test]
+    fn map_urls_leaves_unterminated_urls_alone() {
+        let css = "a { b: url('x.png); }";
+
+        assert_eq!(map_urls(css, |url| Some(format!("/{}", url))), css);
+    }
+
+    #[test]
+    fn prefix_urls_skips_absolute_urls() {
+        let css = "a { b: url(data:image/png;base64,AAAA); c: url(https://x.org/y.png); d: url(/y.png); e: url(#f); }";
+
+        assert_eq!(prefix_urls(css, "/static"), css);
+    }
+
+    #[test]
+    fn prefix_urls_joins_relative_urls() {
+        let css = "@import url(\"theme.css\");\na { b: url(./x.png); c: url(img/y.png); }";
+        let expected = "@import url(\"/static/theme.css\");\na { b: url(/static/x.png); c: url(/static/img/y.png); }";
+
+        assert_eq!(prefix_urls(css, "/static/"), expected);
+    }
+
+    #[test]
+    fn prefix_asset_urls_picks_the_base_by_extension() {
+        let css = "a { b: url('f.woff2?v=3'); c: url(\"icons.SVG#home\"); d: url(other.css); e: url(data:font/woff2;base64,AAAA); }";
+        let expected = "a { b: url('/fonts/f.woff2?v=3'); c: url(\"/img/icons.SVG#home\"); d: url(other.css); e: url(data:font/woff2;base64,AAAA); }";
+
+        assert_eq!(prefix_asset_urls(css, Some("/fonts"), Some("/img")), expected);
+    }
+
+    #[test]
+    fn prefix_asset_urls_without_a_base_leaves_urls_alone() {
+        let css = "@import url(fonts.css);\na { b: url(f.ttf); c: url(x.png); }";
+        let expected = "@import url(fonts.css);\na { b: url(f.ttf); c: url(/img/x.png); }";
+
+        assert_eq!(prefix_asset_urls(css, None, Some("/img")), expected);
+    }
+}
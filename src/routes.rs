@@ -0,0 +1,64 @@
+//! Ready-to-mount routes for development.
+//! Only available in debug builds with the `live_reload` feature
+
+use rocket::serde::{json::Json, Serialize};
+use rocket::State;
+
+use crate::{CompileStats, ContextManager};
+
+/// JSON body returned by [`reload_now`]
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub struct ReloadReport {
+    pub compiled: usize,
+    pub failed: usize,
+    pub errors: Vec<ReloadError>,
+    pub duration_ms: u64,
+    /// The recompile was left to one already in progress
+    pub deferred: bool,
+}
+
+/// A single compile error in a [`ReloadReport`]
+#[derive(Debug, Serialize)]
+#[serde(crate = "rocket::serde")]
+pub struct ReloadError {
+    pub path: String,
+    pub message: String,
+    /// File the error was raised in, which may be a partial loaded by `path`
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+}
+
+impl From<CompileStats> for ReloadReport {
+    fn from(stats: CompileStats) -> Self {
+        Self {
+            compiled: stats.compiled,
+            failed: stats.failed,
+            errors: stats
+                .errors
+                .into_iter()
+                .map(|e| {
+                    let location = e.location();
+
+                    ReloadError {
+                        path: e.path.display().to_string(),
+                        message: e.message,
+                        file: location.as_ref().map(|location| location.file.clone()),
+                        line: location.as_ref().map(|location| location.line),
+                        column: location.map(|location| location.column),
+                    }
+                })
+                .collect(),
+            duration_ms: stats.duration.as_millis() as u64,
+            deferred: stats.deferred,
+        }
+    }
+}
+
+/// Forces a recompile of every sass file and reports the result.
+/// Mount it with `routes![sass_rocket_fairing::reload_now]`
+#[rocket::post("/reload")]
+pub fn reload_now(ctx_manager: &State<ContextManager>) -> Json<ReloadReport> {
+    Json(ctx_manager.recompile_all_force().into())
+}
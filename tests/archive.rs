@@ -0,0 +1,41 @@
+#![cfg(feature = "archive")]
+
+use std::io::{Cursor, Write};
+
+use sass_rocket_fairing::compile_archive;
+use sass_rocket_fairing::rsass::output::Format;
+
+/// Builds a zip archive holding `files` in memory
+fn zip_of(files: &[(&str, &str)]) -> Cursor<Vec<u8>> {
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, contents) in files {
+        writer.start_file(*name, zip::write::FileOptions::default()).unwrap();
+        writer.write_all(contents.as_bytes()).unwrap();
+    }
+
+    Cursor::new(writer.finish().unwrap().into_inner())
+}
+
+#[test]
+fn archives_compile_like_a_sass_dir() {
+    let archive = zip_of(&[
+        ("theme/_colors.scss", "$primary: red;\n"),
+        ("theme/main.scss", "@use 'colors';\na { b: colors.$primary; }\n"),
+        ("print.scss", "a { b: c; }\n"),
+    ]);
+
+    let compiled = compile_archive(archive, Format::default()).unwrap();
+
+    let mut outputs: Vec<&str> = compiled.keys().map(String::as_str).collect();
+    outputs.sort();
+    assert_eq!(outputs, vec!["print.css", "theme/main.css"]);
+    assert!(compiled["theme/main.css"].contains("b: red"));
+}
+
+#[test]
+fn archives_escaping_the_extraction_dir_are_rejected() {
+    let archive = zip_of(&[("main.scss", "a { b: c; }\n"), ("../escaped.scss", "a { b: c; }\n")]);
+
+    assert!(compile_archive(archive, Format::default()).is_err());
+    assert!(!std::env::temp_dir().join("escaped.scss").exists());
+}
@@ -0,0 +1,40 @@
+//! Fixtures shared by the integration tests, not every test file uses all of them
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use rocket::figment::Figment;
+use sass_rocket_fairing::{default_output_name, Context};
+
+/// Creates a fresh scratch directory for a test, canonicalized so it compares
+/// equal to the paths the crate returns
+pub fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("sass-rocket-fairing-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+
+    dir.canonicalize().unwrap()
+}
+
+/// Creates a context compiling `dir/sass` into `dir/css`, creating both
+/// directories, so sources are to be written after calling it
+pub fn context(dir: &Path) -> Context {
+    fs::create_dir_all(dir.join("sass")).unwrap();
+    fs::create_dir_all(dir.join("css")).unwrap();
+
+    Context::initialize(&dir.join("sass"), &dir.join("css"), Default::default(), Arc::new(default_output_name)).unwrap()
+}
+
+/// Returns the config of a rocket compiling `dir/sass` into `dir/css`, creating both directories.
+/// The style is expanded whatever the build profile
+pub fn rocket_config(dir: &Path) -> Figment {
+    fs::create_dir_all(dir.join("sass")).unwrap();
+    fs::create_dir_all(dir.join("css")).unwrap();
+
+    rocket::Config::figment()
+        .merge(("sass_dir", dir.join("sass")))
+        .merge(("css_dir", dir.join("css")))
+        .merge(("sass_style", "expanded"))
+}
@@ -0,0 +1,35 @@
+use std::fs;
+
+use sass_rocket_fairing::compile_file;
+
+mod common;
+
+use common::scratch_dir;
+
+#[test]
+fn css_imports_are_passed_through() {
+    let dir = scratch_dir("css-import");
+    fs::write(dir.join("reset.css"), "html { margin: 0; }\n").unwrap();
+    fs::write(dir.join("main.scss"), "@import 'reset.css';\na { b: c; }\n").unwrap();
+
+    let css = compile_file(dir.join("main.scss"), Default::default()).unwrap();
+
+    assert!(css.starts_with("@import 'reset.css';"), "unexpected output: {}", css);
+    assert!(!css.contains("margin"), "reset.css was inlined: {}", css);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn extensionless_imports_still_resolve_css_files() {
+    let dir = scratch_dir("css-import-inline");
+    fs::write(dir.join("reset.css"), "html { margin: 0; }\n").unwrap();
+    fs::write(dir.join("main.scss"), "@import 'reset';\na { b: c; }\n").unwrap();
+
+    let css = compile_file(dir.join("main.scss"), Default::default()).unwrap();
+
+    assert!(!css.contains("@import"), "unexpected output: {}", css);
+    assert!(css.contains("margin: 0"), "reset.css was not inlined: {}", css);
+
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,49 @@
+use std::fs;
+
+use sass_rocket_fairing::{dependencies, dependencies_with_extensions};
+
+mod common;
+
+use common::scratch_dir;
+
+#[test]
+fn forwarded_modules_are_dependencies() {
+    let dir = scratch_dir("deps-forward");
+    fs::create_dir_all(dir.join("theme")).unwrap();
+    fs::write(dir.join("theme/_colors.scss"), "$primary: red;\n").unwrap();
+    fs::write(dir.join("theme/_index.scss"), "@forward 'colors';\n").unwrap();
+    fs::write(dir.join("_tokens.scss"), "@forward 'theme' as theme-*;\n").unwrap();
+    fs::write(dir.join("main.scss"), "@use 'tokens';\na { color: tokens.$theme-primary; }\n").unwrap();
+
+    let deps = dependencies(&dir.join("main.scss"), &[]);
+
+    assert_eq!(
+        deps,
+        vec![dir.join("_tokens.scss"), dir.join("theme/_colors.scss"), dir.join("theme/_index.scss")]
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn builtin_modules_and_css_imports_are_not_dependencies() {
+    let dir = scratch_dir("deps-builtin");
+    fs::write(dir.join("reset.css"), "html { margin: 0; }\n").unwrap();
+    fs::write(dir.join("main.scss"), "@use 'sass:math';\n@import 'reset.css';\n").unwrap();
+
+    assert!(dependencies(&dir.join("main.scss"), &[]).is_empty());
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn dependencies_resolve_with_the_given_extensions() {
+    let dir = scratch_dir("deps-extensions");
+    fs::write(dir.join("_tokens.pcss"), "$gap: 2px;\n").unwrap();
+    fs::write(dir.join("main.pcss"), "@use 'tokens';\na { gap: tokens.$gap; }\n").unwrap();
+
+    assert!(dependencies(&dir.join("main.pcss"), &[]).is_empty());
+    assert_eq!(dependencies_with_extensions(&dir.join("main.pcss"), &[], &["scss", "pcss"]), vec![dir.join("_tokens.pcss")]);
+
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,57 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use sass_rocket_fairing::ContextManager;
+
+mod common;
+
+use common::{context, scratch_dir};
+
+#[test]
+fn the_manifest_limits_the_compiled_sources() {
+    let dir = scratch_dir("entrypoints-limit");
+    let ctx = context(&dir);
+    fs::write(dir.join("sass/entrypoints.toml"), "entrypoints = [\"main.scss\"]\n").unwrap();
+    fs::write(dir.join("sass/main.scss"), "a { b: c; }\n").unwrap();
+    fs::write(dir.join("sass/other.scss"), "a { b: d; }\n").unwrap();
+
+    let ctx_manager = ContextManager::new(ctx);
+    ctx_manager.compile_all_and_write();
+
+    assert!(dir.join("css/main.css").exists());
+    assert!(!dir.join("css/other.css").exists());
+    assert_eq!(ctx_manager.output_for(Path::new("other.scss")), None);
+    assert_eq!(ctx_manager.sources_for(Path::new("main.css")), vec![PathBuf::from("main.scss")]);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(all(feature = "watch", debug_assertions))]
+#[test]
+fn editing_the_manifest_recompiles() {
+    let dir = scratch_dir("entrypoints-watch");
+    let mut ctx = context(&dir);
+    fs::create_dir_all(dir.join("config")).unwrap();
+    ctx.entrypoints_manifest = dir.join("config/entrypoints.toml");
+    fs::write(dir.join("config/entrypoints.toml"), "entrypoints = [\"main.scss\"]\n").unwrap();
+    fs::write(dir.join("sass/main.scss"), "a { b: c; }\n").unwrap();
+    fs::write(dir.join("sass/other.scss"), "a { b: d; }\n").unwrap();
+
+    let ctx_manager = ContextManager::new(ctx);
+    ctx_manager.compile_all_and_write();
+    assert!(!dir.join("css/other.css").exists());
+
+    // The manifest lives outside sass_dir, so only watching it notices the edit
+    fs::write(dir.join("config/entrypoints.toml"), "entrypoints = [\"main.scss\", \"other.scss\"]\n").unwrap();
+    for _ in 0..50 {
+        ctx_manager.reload_if_needed();
+        if dir.join("css/other.css").exists() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+
+    assert!(dir.join("css/other.css").exists(), "the manifest edit was not picked up");
+
+    fs::remove_dir_all(dir).unwrap();
+}
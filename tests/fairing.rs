@@ -0,0 +1,150 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rocket::error::ErrorKind;
+use rocket::figment::Figment;
+use sass_rocket_fairing::{ContextManager, SassFairing};
+
+mod common;
+
+use common::{rocket_config, scratch_dir};
+
+#[rocket::async_test]
+async fn memory_mode_compiles_at_ignite_without_writing() {
+    let dir = scratch_dir("fairing-memory");
+    let figment = rocket_config(&dir).merge(("sass_mode", "memory"));
+    fs::write(dir.join("sass/main.scss"), "a { b: c; }\n").unwrap();
+
+    let rocket = rocket::custom(figment).attach(SassFairing::default()).ignite().await.unwrap();
+    let ctx_manager = rocket.state::<ContextManager>().unwrap();
+
+    assert!(ctx_manager.compiled_css("main.css").unwrap().contains("b: c"));
+    assert!(fs::read_dir(dir.join("css")).unwrap().next().is_none(), "css was written to css_dir");
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(unix)]
+#[rocket::async_test]
+async fn output_mode_sets_the_permissions_of_outputs() {
+    use rocket::figment::providers::{Format, Toml};
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = scratch_dir("fairing-output-mode");
+    let figment = rocket_config(&dir).merge(Toml::string("sass_output_mode = 0o640"));
+    fs::write(dir.join("sass/main.scss"), "a { b: c; }\n").unwrap();
+
+    let rocket = rocket::custom(figment).attach(SassFairing::default()).ignite().await.unwrap();
+    rocket.state::<ContextManager>().unwrap().compile_all_and_write();
+
+    let mode = fs::metadata(dir.join("css/main.css")).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o640);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[rocket::async_test]
+async fn verify_leaves_css_dir_and_the_output_mode_alone() {
+    let dir = scratch_dir("fairing-verify");
+    let figment = rocket_config(&dir).merge(("sass_verify", true));
+    fs::write(dir.join("sass/main.scss"), "a { b: c; }\n").unwrap();
+    fs::write(dir.join("css/main.css"), "outdated").unwrap();
+
+    let rocket = rocket::custom(figment).attach(SassFairing::default()).ignite().await.unwrap();
+    let ctx_manager = rocket.state::<ContextManager>().unwrap();
+
+    assert_eq!(fs::read_to_string(dir.join("css/main.css")).unwrap(), "outdated");
+    assert!(!ctx_manager.context().in_memory);
+    assert!(ctx_manager.compiled_css("main.css").is_none());
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[rocket::async_test]
+async fn verify_aborts_on_outdated_css() {
+    let dir = scratch_dir("fairing-verify-abort");
+    let figment = rocket_config(&dir).merge(("sass_verify", true)).merge(("sass_abort_on_error", true));
+    fs::write(dir.join("sass/main.scss"), "a { b: c; }\n").unwrap();
+    fs::write(dir.join("css/main.css"), "outdated").unwrap();
+
+    let error = rocket::custom(figment).attach(SassFairing::default()).ignite().await.unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::FailedFairings(_)));
+    assert_eq!(fs::read_to_string(dir.join("css/main.css")).unwrap(), "outdated");
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+/// Returns the config of a rocket compiling `dir/admin/sass` into `dir/admin/css`
+/// and `dir/site/sass` into `dir/site/css` through `sass_dirs`
+fn pairs_config(dir: &Path) -> Figment {
+    use rocket::figment::providers::{Format, Toml};
+
+    let mut toml = String::new();
+    for pair in &["admin", "site"] {
+        fs::create_dir_all(dir.join(pair).join("sass")).unwrap();
+        fs::create_dir_all(dir.join(pair).join("css")).unwrap();
+        toml += &format!(
+            "[[sass_dirs]]\nin = {:?}\nout = {:?}\n",
+            dir.join(pair).join("sass"),
+            dir.join(pair).join("css")
+        );
+    }
+
+    rocket::Config::figment().merge(Toml::string(&toml)).merge(("sass_style", "expanded"))
+}
+
+#[rocket::async_test]
+async fn sass_dirs_compile_every_pair() {
+    let dir = scratch_dir("fairing-pairs");
+    let figment = pairs_config(&dir);
+    fs::write(dir.join("admin/sass/admin.scss"), "a { b: c; }\n").unwrap();
+    fs::write(dir.join("site/sass/site.scss"), "a { b: d; }\n").unwrap();
+
+    let rocket = rocket::custom(figment).attach(SassFairing::default()).ignite().await.unwrap();
+    let ctx_manager = rocket.state::<ContextManager>().unwrap();
+    let stats = ctx_manager.compile_all_and_write();
+
+    assert_eq!((stats.compiled, stats.failed), (2, 0));
+    assert!(fs::read_to_string(dir.join("admin/css/admin.css")).unwrap().contains("b: c"));
+    assert!(fs::read_to_string(dir.join("site/css/site.css")).unwrap().contains("b: d"));
+
+    // Lookups fall back to the linked pair
+    assert_eq!(ctx_manager.output_for(&dir.join("site/sass/site.scss")), Some(PathBuf::from("site.css")));
+    assert_eq!(ctx_manager.sources_for(Path::new("site.css")), vec![PathBuf::from("site.scss")]);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[rocket::async_test]
+async fn abort_on_error_checks_every_pair() {
+    let dir = scratch_dir("fairing-pairs-abort");
+    let figment = pairs_config(&dir).merge(("sass_abort_on_error", true));
+    fs::write(dir.join("admin/sass/admin.scss"), "a { b: c; }\n").unwrap();
+    fs::write(dir.join("site/sass/site.scss"), "a { b: $missing; }\n").unwrap();
+
+    let error = rocket::custom(figment).attach(SassFairing::default()).ignite().await.unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::FailedFairings(_)));
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[rocket::async_test]
+async fn verify_checks_every_pair() {
+    let dir = scratch_dir("fairing-pairs-verify");
+    let figment = pairs_config(&dir);
+    fs::write(dir.join("admin/sass/admin.scss"), "a { b: c; }\n").unwrap();
+    fs::write(dir.join("site/sass/site.scss"), "a { b: d; }\n").unwrap();
+
+    let rocket = rocket::custom(figment.clone()).attach(SassFairing::default()).ignite().await.unwrap();
+    let ctx_manager = rocket.state::<ContextManager>().unwrap();
+    ctx_manager.compile_all_and_write();
+    fs::write(dir.join("site/css/site.css"), "outdated").unwrap();
+
+    assert_eq!(ctx_manager.verify().0, vec![dir.join("site/css/site.css")]);
+
+    let figment = figment.merge(("sass_verify", true)).merge(("sass_abort_on_error", true));
+    let error = rocket::custom(figment).attach(SassFairing::default()).ignite().await.unwrap_err();
+    assert!(matches!(error.kind(), ErrorKind::FailedFairings(_)));
+
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,42 @@
+use std::fs;
+
+use sass_rocket_fairing::rsass::css::Value;
+use sass_rocket_fairing::{ContextManager, SassFunction};
+
+mod common;
+
+use common::{context, scratch_dir};
+
+#[test]
+fn functions_are_callable_from_sass() {
+    let dir = scratch_dir("functions-call");
+    let mut ctx = context(&dir);
+    ctx.functions = vec![SassFunction::new("shade", &["name"], |args| match &args[0] {
+        Value::Literal(name) => Ok(Value::Literal(format!("{}-dark", name.value()).into())),
+        _ => Err("expected a name".to_string()),
+    })];
+    fs::write(dir.join("sass/main.scss"), "a { b: shade(\"primary\"); }\n").unwrap();
+
+    let stats = ContextManager::new(ctx).compile_all_and_write();
+
+    assert_eq!(stats.failed, 0, "{:?}", stats.errors);
+    assert!(fs::read_to_string(dir.join("css/main.css")).unwrap().contains("b: primary-dark"));
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn function_errors_fail_the_compile() {
+    let dir = scratch_dir("functions-error");
+    let mut ctx = context(&dir);
+    ctx.functions = vec![SassFunction::new("setting", &[], |_| Err("no such setting".to_string()))];
+    fs::write(dir.join("sass/main.scss"), "a { b: setting(); }\n").unwrap();
+
+    let stats = ContextManager::new(ctx).compile_all_and_write();
+
+    assert_eq!(stats.failed, 1);
+    assert!(stats.errors[0].message.contains("no such setting"), "unexpected error: {}", stats.errors[0].message);
+    assert!(!dir.join("css/main.css").exists());
+
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,35 @@
+use std::fs;
+use std::sync::Arc;
+
+use sass_rocket_fairing::ContextManager;
+
+mod common;
+
+use common::{context, scratch_dir};
+
+#[test]
+fn importers_resolve_urls_before_the_filesystem() {
+    let dir = scratch_dir("importers-resolve");
+    let mut ctx = context(&dir);
+    ctx.importers = vec![Arc::new(|url: &str| match url {
+        "theme:dark" => Some("$bg: black;\n".to_string()),
+        "colors" => Some("$primary: blue;\n".to_string()),
+        _ => None,
+    })];
+    fs::write(dir.join("sass/_colors.scss"), "$primary: red;\n").unwrap();
+    fs::write(dir.join("sass/_sizes.scss"), "$gap: 2px;\n").unwrap();
+    fs::write(
+        dir.join("sass/main.scss"),
+        "@use 'theme:dark' as theme;\n@use 'colors';\n@use 'sizes';\na { b: theme.$bg; c: colors.$primary; d: sizes.$gap; }\n",
+    )
+    .unwrap();
+
+    let stats = ContextManager::new(ctx).compile_all_and_write();
+
+    assert_eq!(stats.failed, 0, "{:?}", stats.errors);
+    let compiled = fs::read_to_string(dir.join("css/main.css")).unwrap();
+    // Files are only looked for when no importer handles the url
+    assert!(compiled.contains("b: black") && compiled.contains("c: blue") && compiled.contains("d: 2px"), "unexpected css: {}", compiled);
+
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,326 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use sass_rocket_fairing::{CacheBust, ContextManager, LineEndings, OutputLayout, OutputStyle};
+
+mod common;
+
+use common::{context, scratch_dir};
+
+#[test]
+fn copied_css_is_not_formatted() {
+    let dir = scratch_dir("outputs-copy");
+    let mut ctx = context(&dir);
+    ctx.copy_css = true;
+    ctx.indent = Some("\t".into());
+    ctx.line_endings = Some(LineEndings::Crlf);
+    fs::write(dir.join("sass/reset.css"), "html {\n    margin: 0;\n}\n").unwrap();
+    fs::write(dir.join("sass/main.scss"), "a { b: c; }\n").unwrap();
+
+    let ctx_manager = ContextManager::new(ctx);
+    ctx_manager.compile_all_and_write();
+
+    let copied = fs::read_to_string(dir.join("css/reset.css")).unwrap();
+    assert_eq!(copied, "html {\n    margin: 0;\n}\n");
+    let compiled = fs::read_to_string(dir.join("css/main.css")).unwrap();
+    assert!(compiled.contains("\r\n\tb: c;"), "main.css was not formatted: {:?}", compiled);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn variant_outputs_map_back_to_their_source() {
+    let dir = scratch_dir("outputs-variants");
+    let mut ctx = context(&dir);
+    ctx.variants = vec![OutputStyle::Expanded, OutputStyle::Compressed];
+    fs::write(dir.join("sass/main.scss"), "a { b: c; }\n").unwrap();
+
+    let ctx_manager = ContextManager::new(ctx);
+    let outputs = vec![PathBuf::from("main.css"), PathBuf::from("main.min.css")];
+    assert_eq!(ctx_manager.outputs_for(Path::new("main.scss")), outputs);
+    assert_eq!(ctx_manager.sources_for(Path::new("main.min.css")), vec![PathBuf::from("main.scss")]);
+
+    // Lazy compiles go through the same mapping
+    let stats = ctx_manager.compile_output_if_stale(Path::new("main.min.css")).unwrap();
+    assert_eq!(stats.failed, 0);
+    assert!(fs::read_to_string(dir.join("css/main.min.css")).unwrap().starts_with("a{b:c}"));
+    assert!(ctx_manager.compile_output_if_stale(Path::new("main.min.css")).is_none());
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn variants_use_the_dep_files_of_their_source() {
+    let dir = scratch_dir("outputs-variant-deps");
+    let mut ctx = context(&dir);
+    ctx.variants = vec![OutputStyle::Expanded, OutputStyle::Compressed];
+    ctx.dep_files = true;
+    fs::write(dir.join("sass/_colors.scss"), "$primary: red;\n").unwrap();
+    fs::write(dir.join("sass/main.scss"), "@use 'colors';\na { b: colors.$primary; }\n").unwrap();
+
+    ContextManager::new(ctx).compile_all_and_write();
+
+    for output in &["main.css", "main.min.css"] {
+        let dep_file = fs::read_to_string(dir.join("css").join(format!("{}.d", output))).unwrap();
+        let expected = format!(
+            "{}: {} {}\n",
+            dir.join("css").join(output).display(),
+            dir.join("sass/main.scss").display(),
+            dir.join("sass/_colors.scss").display()
+        );
+        assert_eq!(dep_file, expected);
+    }
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn variants_of_inline_sources_stay_in_memory() {
+    let dir = scratch_dir("outputs-variant-inline");
+    let mut ctx = context(&dir);
+    ctx.variants = vec![OutputStyle::Expanded, OutputStyle::Compressed];
+    ctx.inline_patterns = vec![glob::Pattern::new("main.scss").unwrap()];
+    fs::write(dir.join("sass/main.scss"), "a { b: c; }\n").unwrap();
+
+    let ctx_manager = ContextManager::new(ctx);
+    ctx_manager.compile_all_and_write();
+
+    assert!(fs::read_dir(dir.join("css")).unwrap().next().is_none(), "an inline source was written");
+    assert!(ctx_manager.inline_css("main.css").unwrap().contains("b: c"));
+    assert_eq!(ctx_manager.inline_css("main.min.css").unwrap(), "a{b:c}\n");
+    assert_eq!(ctx_manager.verify().0, Vec::<PathBuf>::new());
+
+    // Writing a map from compile_all maps the variants back the same way
+    let (compiled, _) = ctx_manager.compile_all();
+    assert_eq!(ctx_manager.write_compiled(compiled), Vec::<PathBuf>::new());
+    assert!(fs::read_dir(dir.join("css")).unwrap().next().is_none(), "an inline source was written");
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+/// Lists the hashed copies of `main.css` in `dir/css`
+fn hashed_copies(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir.join("css"))
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|name| name.starts_with("main.") && name != "main.css" && !name.ends_with(".d"))
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn hashed_copies_replace_the_previous_one() {
+    let dir = scratch_dir("outputs-hashed");
+    let mut ctx = context(&dir);
+    ctx.cache_bust = CacheBust::Filename;
+    fs::write(dir.join("sass/main.scss"), "a { b: c; }\n").unwrap();
+
+    let ctx_manager = ContextManager::new(ctx);
+    ctx_manager.compile_all_and_write();
+    let first = hashed_copies(&dir);
+    assert_eq!(first.len(), 1);
+    assert_eq!(ctx_manager.asset_url("main.css"), Some(first[0].clone()));
+
+    fs::write(dir.join("sass/main.scss"), "a { b: d; }\n").unwrap();
+    ctx_manager.compile_all_and_write();
+    let second = hashed_copies(&dir);
+    assert_eq!(second.len(), 1);
+    assert_ne!(first, second);
+    assert_eq!(ctx_manager.asset_url("main.css"), Some(second[0].clone()));
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn file_mode_applies_to_every_written_file() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = scratch_dir("outputs-file-mode");
+    let mut ctx = context(&dir);
+    ctx.cache_bust = CacheBust::Filename;
+    ctx.file_mode = Some(0o640);
+    fs::write(dir.join("sass/main.scss"), "a { b: c; }\n").unwrap();
+
+    ContextManager::new(ctx).compile_all_and_write();
+
+    for name in hashed_copies(&dir).iter().chain(&["main.css".to_string()]) {
+        let mode = fs::metadata(dir.join("css").join(name)).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o640, "wrong mode for {}", name);
+    }
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn dep_files_list_the_source_and_what_it_loads() {
+    let dir = scratch_dir("outputs-dep-files");
+    let mut ctx = context(&dir);
+    ctx.dep_files = true;
+    fs::write(dir.join("sass/_colors.scss"), "$primary: red;\n").unwrap();
+    fs::write(dir.join("sass/main.scss"), "@use 'colors';\na { b: colors.$primary; }\n").unwrap();
+
+    ContextManager::new(ctx).compile_all_and_write();
+
+    let dep_file = fs::read_to_string(dir.join("css/main.css.d")).unwrap();
+    let expected = format!(
+        "{}: {} {}\n",
+        dir.join("css/main.css").display(),
+        dir.join("sass/main.scss").display(),
+        dir.join("sass/_colors.scss").display()
+    );
+    assert_eq!(dep_file, expected);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn planned_outputs_include_dep_files_and_hashed_copies() {
+    let dir = scratch_dir("outputs-planned");
+    let mut ctx = context(&dir);
+    ctx.cache_bust = CacheBust::Filename;
+    ctx.dep_files = true;
+    fs::write(dir.join("sass/_colors.scss"), "$primary: red;\n").unwrap();
+    fs::write(dir.join("sass/main.scss"), "a { b: c; }\n").unwrap();
+
+    let ctx_manager = ContextManager::new(ctx);
+    assert_eq!(ctx_manager.planned_outputs(), vec![dir.join("css/main.css"), dir.join("css/main.css.d")]);
+
+    ctx_manager.compile_all_and_write();
+    let hashed = hashed_copies(&dir);
+    let planned = vec![dir.join("css").join(&hashed[0]), dir.join("css/main.css"), dir.join("css/main.css.d")];
+    assert_eq!(ctx_manager.planned_outputs(), planned);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn flat_strict_collisions_fail_the_pass() {
+    let dir = scratch_dir("outputs-collisions");
+    let mut ctx = context(&dir);
+    ctx.output_layout = OutputLayout::FlatStrict;
+    for sub_dir in &["sass/admin", "sass/site"] {
+        fs::create_dir_all(dir.join(sub_dir)).unwrap();
+    }
+    for source in &["admin/main.scss", "site/main.scss", "site/print.scss", "site/_part.scss"] {
+        fs::write(dir.join("sass").join(source), "a { b: c; }\n").unwrap();
+    }
+
+    let ctx_manager = ContextManager::new(ctx);
+    let stats = ctx_manager.compile_all_and_write();
+
+    assert_eq!(stats.failed, 1);
+    let message = &stats.errors[0].message;
+    assert!(message.contains("'admin/main.scss'") && message.contains("'site/main.scss'"), "unexpected error: {}", message);
+    assert!(message.contains("'main.css'") && !message.contains("print"), "unexpected error: {}", message);
+    assert!(!dir.join("css/main.css").exists() && !dir.join("css/print.css").exists());
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn listed_extensions_are_compiled() {
+    let dir = scratch_dir("outputs-extensions");
+    let mut ctx = context(&dir);
+    ctx.extensions = vec!["scss".to_string(), "pcss".to_string()];
+    fs::write(dir.join("sass/_colors.scss"), "$primary: red;\n").unwrap();
+    fs::write(dir.join("sass/_sizes.pcss"), "$gap: 2px;\n").unwrap();
+    fs::write(dir.join("sass/_borders.pcss"), "$width: 1px;\n").unwrap();
+    fs::write(
+        dir.join("sass/main.pcss"),
+        "@use 'colors';\n@use 'sizes';\n@use '_borders.pcss' as borders;\na { b: colors.$primary; c: sizes.$gap; d: borders.$width; }\n",
+    )
+    .unwrap();
+    fs::write(dir.join("sass/notes.txt"), "not a source").unwrap();
+
+    let stats = ContextManager::new(ctx).compile_all_and_write();
+
+    assert_eq!((stats.compiled, stats.failed), (1, 0), "{:?}", stats.errors);
+    let compiled = fs::read_to_string(dir.join("css/main.css")).unwrap();
+    assert!(compiled.contains("b: red") && compiled.contains("c: 2px") && compiled.contains("d: 1px"), "unexpected css: {}", compiled);
+    assert!(!dir.join("css/notes.css").exists());
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn output_lookups_map_sources_and_outputs_both_ways() {
+    let dir = scratch_dir("outputs-lookups");
+    let ctx = context(&dir);
+    for sub_dir in &["sass/admin", "sass/site"] {
+        fs::create_dir_all(dir.join(sub_dir)).unwrap();
+    }
+    for source in &["admin/main.scss", "site/main.scss", "site/_part.scss"] {
+        fs::write(dir.join("sass").join(source), "a { b: c; }\n").unwrap();
+    }
+
+    let ctx_manager = ContextManager::new(ctx);
+    assert_eq!(ctx_manager.output_for(Path::new("admin/main.scss")), Some(PathBuf::from("main.css")));
+    assert_eq!(ctx_manager.output_for(&dir.join("sass/site/main.scss")), Some(PathBuf::from("main.css")));
+    assert_eq!(ctx_manager.output_for(Path::new("site/_part.scss")), None);
+    assert_eq!(ctx_manager.output_for(Path::new("missing.scss")), None);
+
+    // The default flat naming sends both to the same output
+    let sources = vec![PathBuf::from("admin/main.scss"), PathBuf::from("site/main.scss")];
+    assert_eq!(ctx_manager.sources_for(Path::new("main.css")), sources);
+    assert!(ctx_manager.sources_for(Path::new("part.css")).is_empty());
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn writer_receives_the_outputs_instead_of_css_dir() {
+    use sass_rocket_fairing::SassError;
+    use std::sync::{Arc, Mutex};
+
+    let dir = scratch_dir("outputs-writer");
+    let mut ctx = context(&dir);
+    let written = Arc::new(Mutex::new(Vec::new()));
+    ctx.writer = {
+        let written = written.clone();
+        Some(Arc::new(move |path: &Path, css: &str| {
+            if path == Path::new("broken.css") {
+                return Err(SassError::new(path, "upload failed"));
+            }
+            written.lock().unwrap().push((path.to_path_buf(), css.to_string()));
+            Ok(())
+        }))
+    };
+    fs::create_dir_all(dir.join("sass/admin")).unwrap();
+    fs::write(dir.join("sass/admin/app.scss"), "a { b: c; }\n").unwrap();
+    fs::write(dir.join("sass/broken.scss"), "a { b: d; }\n").unwrap();
+
+    let stats = ContextManager::new(ctx).compile_all_and_write();
+
+    assert_eq!(stats.changed, vec![PathBuf::from("app.css")]);
+    assert_eq!(stats.errors[0].message, "upload failed");
+    let written = written.lock().unwrap();
+    assert_eq!(written.len(), 1);
+    assert_eq!(written[0].0, PathBuf::from("app.css"));
+    assert!(written[0].1.contains("b: c"));
+    assert!(fs::read_dir(dir.join("css")).unwrap().next().is_none(), "css was written to css_dir");
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn versions_follow_the_current_css() {
+    let dir = scratch_dir("outputs-versions");
+    let ctx = context(&dir);
+    fs::write(dir.join("sass/main.scss"), "a { b: c; }\n").unwrap();
+
+    let ctx_manager = ContextManager::new(ctx);
+    assert_eq!(ctx_manager.version_of("main.css"), None);
+
+    ctx_manager.compile_all_and_write();
+    let first = ctx_manager.version_of("main.css").unwrap();
+    assert_eq!(ctx_manager.version_of("main.css"), Some(first.clone()));
+
+    fs::write(dir.join("sass/main.scss"), "a { b: d; }\n").unwrap();
+    ctx_manager.compile_all_and_write();
+    assert_ne!(ctx_manager.version_of("main.css").unwrap(), first);
+    assert_eq!(ctx_manager.version_of("missing.css"), None);
+
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,205 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+
+use sass_rocket_fairing::ContextManager;
+
+mod common;
+
+use common::{context, scratch_dir};
+
+#[test]
+fn forced_recompiles_bypass_the_cache() {
+    let dir = scratch_dir("recompile-cache");
+    let mut ctx = context(&dir);
+    ctx.cache_dir = Some(dir.join("cache"));
+    fs::write(dir.join("sass/main.scss"), "a { b: c; }\n").unwrap();
+
+    let ctx_manager = ContextManager::new(ctx);
+    ctx_manager.compile_all_and_write();
+
+    let entries: Vec<PathBuf> = fs::read_dir(dir.join("cache")).unwrap().map(|entry| entry.unwrap().path()).collect();
+    assert_eq!(entries.len(), 1);
+    fs::write(&entries[0], "STALE").unwrap();
+
+    ctx_manager.compile_all_and_write();
+    assert_eq!(fs::read_to_string(dir.join("css/main.css")).unwrap(), "STALE");
+
+    ctx_manager.recompile_all_force();
+    let css = fs::read_to_string(dir.join("css/main.css")).unwrap();
+    assert!(css.contains("b: c"), "cached css was reused: {}", css);
+    assert_eq!(fs::read_to_string(&entries[0]).unwrap(), css);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn forced_recompiles_forget_previous_state() {
+    let dir = scratch_dir("recompile-state");
+    let mut ctx = context(&dir);
+    ctx.in_memory = true;
+    fs::write(dir.join("sass/main.scss"), "a { b: ; }\n").unwrap();
+    fs::write(dir.join("sass/old.scss"), "a { b: c; }\n").unwrap();
+
+    let ctx_manager = ContextManager::new(ctx);
+    ctx_manager.compile_all_and_write();
+    assert!(ctx_manager.last_error("main.css").is_some());
+    assert!(ctx_manager.compiled_css("old.css").is_some());
+
+    fs::remove_file(dir.join("sass/main.scss")).unwrap();
+    fs::remove_file(dir.join("sass/old.scss")).unwrap();
+    ctx_manager.recompile_all_force();
+
+    assert!(ctx_manager.last_error("main.css").is_none());
+    assert!(ctx_manager.compiled_css("old.css").is_none());
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn every_failing_source_is_reported() {
+    let dir = scratch_dir("recompile-failures");
+    let ctx = context(&dir);
+    fs::write(dir.join("sass/a.scss"), "a { b: ; }\n").unwrap();
+    fs::write(dir.join("sass/b.scss"), "a { b: ; }\n").unwrap();
+    fs::write(dir.join("sass/c.scss"), "a { b: c; }\n").unwrap();
+
+    let ctx_manager = ContextManager::new(ctx);
+    let stats = ctx_manager.compile_all_and_write();
+
+    assert_eq!((stats.compiled, stats.failed, stats.errors.len()), (1, 2, 2));
+    assert!(ctx_manager.last_error("a.css").is_some());
+    assert!(ctx_manager.last_error("b.css").is_some());
+    assert!(dir.join("css/c.css").exists());
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn forced_recompiles_requested_during_a_pass_are_not_downgraded() {
+    let dir = scratch_dir("recompile-overlap");
+    let mut ctx = context(&dir);
+    ctx.in_memory = true;
+    fs::write(dir.join("sass/main.scss"), "a { b: c; }\n").unwrap();
+    fs::write(dir.join("sass/old.scss"), "a { b: c; }\n").unwrap();
+
+    let ctx_manager = ContextManager::new(ctx);
+    ctx_manager.compile_all_and_write();
+    assert!(ctx_manager.compiled_css("old.css").is_some());
+    fs::remove_file(dir.join("sass/old.scss")).unwrap();
+
+    // Hold up the next pass until the forced recompile was requested
+    let (started_tx, started_rx) = mpsc::channel();
+    let (release_tx, release_rx) = mpsc::channel::<()>();
+    let gate = Mutex::new(Some((started_tx, release_rx)));
+    ctx_manager.context_mut().transforms.push(Arc::new(move |css: &str| {
+        if let Some((started, release)) = gate.lock().unwrap().take() {
+            started.send(()).unwrap();
+            release.recv().unwrap();
+        }
+        Ok(css.to_string())
+    }));
+
+    let running = {
+        let ctx_manager = ctx_manager.clone();
+        thread::spawn(move || ctx_manager.compile_all_and_write())
+    };
+    started_rx.recv().unwrap();
+
+    assert!(ctx_manager.recompile_all_force().deferred);
+    release_tx.send(()).unwrap();
+    assert!(!running.join().unwrap().deferred);
+
+    // Only the forced pass forgets the css of sources that are gone
+    assert!(ctx_manager.compiled_css("old.css").is_none());
+    assert!(ctx_manager.compiled_css("main.css").is_some());
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[rocket::async_test]
+async fn compile_events_report_every_pass() {
+    use rocket::futures::StreamExt;
+
+    let dir = scratch_dir("recompile-events");
+    let ctx = context(&dir);
+    fs::write(dir.join("sass/main.scss"), "a { b: c; }\n").unwrap();
+
+    let ctx_manager = ContextManager::new(ctx);
+    let events = ctx_manager.compile_events();
+    rocket::futures::pin_mut!(events);
+
+    ctx_manager.compile_all_and_write();
+    let event = events.next().await.unwrap();
+    assert!(event.is_ok());
+    assert_eq!(event.changed, vec![PathBuf::from("main.css")]);
+
+    fs::write(dir.join("sass/main.scss"), "a { b: $missing; }\n").unwrap();
+    ctx_manager.compile_all_and_write();
+    let event = events.next().await.unwrap();
+    assert!(!event.is_ok());
+    assert!(event.changed.is_empty());
+    assert_eq!(event.errors[0].path, dir.join("sass/main.scss"));
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn compile_changed_since_follows_changed_dependencies() {
+    use std::time::{Duration, SystemTime};
+
+    let dir = scratch_dir("recompile-changed-since");
+    let ctx = context(&dir);
+    fs::write(dir.join("sass/_colors.scss"), "$primary: red;\n").unwrap();
+    fs::write(dir.join("sass/main.scss"), "@use 'colors';\na { b: colors.$primary; }\n").unwrap();
+    fs::write(dir.join("sass/other.scss"), "a { b: c; }\n").unwrap();
+
+    let ctx_manager = ContextManager::new(ctx);
+    ctx_manager.compile_all_and_write();
+    fs::write(dir.join("css/main.css"), "outdated").unwrap();
+    fs::write(dir.join("css/other.css"), "outdated").unwrap();
+
+    // Later than `since` whatever the timestamp resolution
+    let since = SystemTime::now();
+    fs::write(dir.join("sass/_colors.scss"), "$primary: blue;\n").unwrap();
+    let partial = fs::File::options().write(true).open(dir.join("sass/_colors.scss")).unwrap();
+    partial.set_modified(since + Duration::from_secs(2)).unwrap();
+
+    assert_eq!(ctx_manager.compile_changed_since(since).unwrap(), vec![PathBuf::from("main.css")]);
+    assert!(fs::read_to_string(dir.join("css/main.css")).unwrap().contains("b: blue"));
+    assert_eq!(fs::read_to_string(dir.join("css/other.css")).unwrap(), "outdated");
+
+    fs::write(dir.join("sass/other.scss"), "a { b: $missing; }\n").unwrap();
+    let source = fs::File::options().write(true).open(dir.join("sass/other.scss")).unwrap();
+    source.set_modified(since + Duration::from_secs(2)).unwrap();
+
+    let error = ctx_manager.compile_changed_since(since).unwrap_err();
+    assert_eq!(error.path, dir.join("sass/other.scss"));
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn recompile_sender_triggers_a_pass() {
+    let dir = scratch_dir("recompile-sender");
+    let ctx = context(&dir);
+    fs::write(dir.join("sass/main.scss"), "a { b: c; }\n").unwrap();
+
+    let ctx_manager = ContextManager::new(ctx);
+    let sender = ctx_manager.recompile_sender();
+    sender.send(()).unwrap();
+
+    // The pass runs on a background thread
+    let compiled = || fs::read_to_string(dir.join("css/main.css")).is_ok_and(|css| css.contains("b: c"));
+    for _ in 0..50 {
+        if compiled() {
+            break;
+        }
+        thread::sleep(std::time::Duration::from_millis(100));
+    }
+
+    assert!(compiled(), "no pass ran");
+
+    fs::remove_dir_all(dir).unwrap();
+}
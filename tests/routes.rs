@@ -0,0 +1,126 @@
+use std::fs;
+
+use rocket::http::{ContentType, Header, Status};
+use rocket::local::blocking::Client;
+use sass_rocket_fairing::{CompiledCss, SassFairing};
+
+mod common;
+
+use common::{rocket_config, scratch_dir};
+
+#[test]
+fn mounted_routes_serve_css_kept_in_memory() {
+    let dir = scratch_dir("routes-memory");
+    let figment = rocket_config(&dir).merge(("sass_mode", "memory"));
+    fs::write(dir.join("sass/main.scss"), "a { b: c; }\n").unwrap();
+
+    let rocket = rocket::custom(figment).attach(SassFairing::default());
+    let client = Client::tracked(sass_rocket_fairing::mount_routes(rocket, "/css")).unwrap();
+
+    let response = client.get("/css/main.css").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.content_type(), Some(ContentType::CSS));
+    assert!(response.into_string().unwrap().contains("b: c"));
+
+    assert_eq!(client.get("/css/missing.css").dispatch().status(), Status::NotFound);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn mounted_named_routes_serve_their_instance() {
+    let dir = scratch_dir("routes-named");
+    let figment = rocket::Config::figment()
+        .merge(("admin.sass_dir", dir.join("sass")))
+        .merge(("admin.css_dir", dir.join("css")))
+        .merge(("admin.sass_mode", "memory"))
+        .merge(("admin.sass_style", "expanded"));
+    fs::create_dir_all(dir.join("sass")).unwrap();
+    fs::create_dir_all(dir.join("css")).unwrap();
+    fs::write(dir.join("sass/main.scss"), "a { b: c; }\n").unwrap();
+
+    let rocket = rocket::custom(figment).attach(SassFairing::default().named("admin"));
+    let client = Client::tracked(sass_rocket_fairing::mount_named_routes(rocket, "admin", "/admin/css")).unwrap();
+
+    let response = client.get("/admin/css/main.css").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert!(response.into_string().unwrap().contains("b: c"));
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(debug_assertions)]
+#[test]
+fn lazy_outputs_compile_on_request() {
+    use rocket::fs::FileServer;
+    use std::time::{Duration, SystemTime};
+
+    let dir = scratch_dir("routes-lazy");
+    let figment = rocket_config(&dir).merge(("sass_lazy", true));
+    fs::write(dir.join("sass/main.scss"), "a { b: c; }\n").unwrap();
+
+    let rocket = rocket::custom(figment).attach(SassFairing::default());
+    let rocket = sass_rocket_fairing::mount_routes(rocket, "/css").mount("/css", FileServer::from(dir.join("css")).rank(11));
+    let client = Client::tracked(rocket).unwrap();
+
+    let response = client.get("/css/main.css").dispatch();
+    assert_eq!(response.status(), Status::Ok);
+    assert!(response.into_string().unwrap().contains("b: c"));
+
+    // Newer than the output whatever the timestamp resolution
+    fs::write(dir.join("sass/main.scss"), "a { b: d; }\n").unwrap();
+    let source = fs::File::options().write(true).open(dir.join("sass/main.scss")).unwrap();
+    source.set_modified(SystemTime::now() + Duration::from_secs(2)).unwrap();
+    let response = client.get("/css/main.css").dispatch();
+    assert!(response.into_string().unwrap().contains("b: d"));
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[rocket::get("/compiled/<name>")]
+fn compiled(name: &str) -> CompiledCss {
+    CompiledCss::new(name)
+}
+
+#[test]
+fn css_from_memory_is_tagged_for_caching() {
+    let dir = scratch_dir("routes-etag");
+    let figment = rocket_config(&dir).merge(("sass_mode", "memory"));
+    fs::write(dir.join("sass/main.scss"), "a { b: c; }\n").unwrap();
+
+    let rocket = rocket::custom(figment).attach(SassFairing::default()).mount("/", rocket::routes![compiled]);
+    let client = Client::tracked(sass_rocket_fairing::mount_routes(rocket, "/css")).unwrap();
+
+    for uri in &["/css/main.css", "/compiled/main.css"] {
+        let response = client.get(*uri).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+        assert_eq!(response.headers().get_one("Content-Type"), Some("text/css; charset=utf-8"));
+        assert_eq!(response.headers().get_one("Cache-Control"), Some("no-cache"));
+        let etag = response.headers().get_one("ETag").unwrap().to_string();
+
+        let response = client.get(*uri).header(Header::new("If-None-Match", etag.clone())).dispatch();
+        assert_eq!(response.status(), Status::NotModified);
+        assert_eq!(response.headers().get_one("ETag"), Some(etag.as_str()));
+        assert!(response.into_string().unwrap_or_default().is_empty());
+
+        let response = client.get(*uri).header(Header::new("If-None-Match", "\"stale\"")).dispatch();
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn cache_max_age_lets_browsers_keep_css() {
+    let dir = scratch_dir("routes-max-age");
+    let figment = rocket_config(&dir).merge(("sass_mode", "memory")).merge(("sass_cache_max_age", 600));
+    fs::write(dir.join("sass/main.scss"), "a { b: c; }\n").unwrap();
+
+    let rocket = rocket::custom(figment).attach(SassFairing::default());
+    let client = Client::tracked(sass_rocket_fairing::mount_routes(rocket, "/css")).unwrap();
+
+    let response = client.get("/css/main.css").dispatch();
+    assert_eq!(response.headers().get_one("Cache-Control"), Some("public, max-age=600"));
+
+    fs::remove_dir_all(dir).unwrap();
+}